@@ -1,13 +1,13 @@
-use crate::models::TokenizedTranscript;
+use crate::models::{ChangeStage, ReasonCode, TokenizedTranscript};
 
 use super::micro_turns::rebuild_turns;
 use super::HeuristicsResult;
 
 /// Apply backchannel rules
 ///
-/// Single-word acknowledgements in overlap-adjacent zones should default
-/// to the speaker who is NOT holding the floor (the listener), unless
-/// speaker confidence is high.
+/// Single-word acknowledgements in overlapped speech or at tight handoffs
+/// should default to the speaker who is NOT holding the floor (the
+/// listener), unless speaker confidence is high.
 pub fn apply_backchannel_rules(
     transcript: &mut TokenizedTranscript,
     backchannel_words: &[String],
@@ -16,24 +16,26 @@ pub fn apply_backchannel_rules(
     let mut needs_llm = false;
 
     // First pass: identify candidate backchannels (read-only)
-    let candidates: Vec<(usize, u32, bool, f64)> = transcript
+    let candidates: Vec<(usize, u32, bool, Option<f64>)> = transcript
         .tokens
         .iter()
         .enumerate()
         .filter_map(|(i, token)| {
             let word_lower = token.word.to_lowercase();
-            let is_backchannel = backchannel_words.iter().any(|b| word_lower == *b);
+            let is_backchannel = backchannel_words.contains(&word_lower);
 
-            if !is_backchannel {
+            if !is_backchannel || token.locked {
                 return None;
             }
 
-            // Only consider if in overlap region or low confidence
-            if !token.is_overlap_region && token.speaker_conf >= 0.7 {
+            // Only consider if overlapped, squeezed into a handoff or not
+            // known to be confident
+            let confident = token.speaker_confidence().is_some_and(|c| c >= 0.7);
+            if !token.is_overlap_region && !token.is_tight_handoff && confident {
                 return None;
             }
 
-            Some((i, token.speaker, token.is_overlap_region, token.speaker_conf))
+            Some((i, token.speaker, token.is_overlap_region, token.speaker_confidence()))
         })
         .collect();
 
@@ -43,17 +45,23 @@ pub fn apply_backchannel_rules(
         let floor_holder = find_floor_holder(transcript, i, 5000); // 5 second context
 
         // If the backchannel is attributed to the floor holder, it's probably wrong
-        if let Some(holder) = floor_holder {
-            if current_speaker == holder {
-                // Find an alternative speaker (the listener)
-                let listener = find_listener(transcript, i, holder);
-                if let Some(new_speaker) = listener {
-                    transcript.tokens[i].speaker = new_speaker;
-                    changed_indices.push(i);
-                } else {
-                    // Can't determine listener - need LLM
-                    needs_llm = true;
-                }
+        if let Some(holder) = floor_holder
+            && current_speaker == holder
+        {
+            // Find an alternative speaker (the listener)
+            let listener = find_listener(transcript, i, holder);
+            if let Some(new_speaker) = listener {
+                transcript.tokens[i].relabel(
+                    new_speaker,
+                    ChangeStage::Heuristics,
+                    "apply_backchannel_rules",
+                    ReasonCode::BackchannelAttribution,
+                    None,
+                );
+                changed_indices.push(i);
+            } else {
+                // Can't determine listener - need LLM
+                needs_llm = true;
             }
         }
     }
@@ -66,6 +74,7 @@ pub fn apply_backchannel_rules(
         tokens_relabeled: changed_indices.len(),
         changed_indices,
         needs_llm,
+        speaker_folds: vec![],
     }
 }
 
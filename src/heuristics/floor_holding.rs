@@ -1,4 +1,4 @@
-use crate::models::TokenizedTranscript;
+use crate::models::{ChangeStage, ReasonCode, TokenizedTranscript};
 
 use super::{HeuristicsConfig, HeuristicsResult};
 use super::micro_turns::rebuild_turns;
@@ -94,8 +94,8 @@ pub fn apply_floor_holding(
         // Update floor state
         floor_state.update(token.speaker, duration, timestamp, config);
 
-        // Skip if confidence is high
-        if token.speaker_conf >= 0.8 {
+        // Skip if confidence is high or the label was confirmed in review
+        if token.speaker_confidence().is_some_and(|c| c >= 0.8) || token.locked {
             continue;
         }
 
@@ -103,16 +103,24 @@ pub fn apply_floor_holding(
         if is_rapid_floor_flip(transcript, i, &floor_state, config) {
             let floor_holder = floor_state.floor_holder(config.min_floor_score);
 
-            if let Some(holder) = floor_holder {
-                if transcript.tokens[i].speaker != holder {
-                    // This might be a misattributed token
-                    // Check if surrounding tokens suggest it should be the floor holder
-                    if should_relabel_to_floor_holder(transcript, i, holder) {
-                        transcript.tokens[i].speaker = holder;
-                        changed_indices.push(i);
-                    } else {
-                        needs_llm = true;
-                    }
+            if let Some(holder) = floor_holder
+                && transcript.tokens[i].speaker != holder
+            {
+                // This might be a misattributed token, unless a pause sets it
+                // apart; check if surrounding tokens suggest the floor holder
+                if transcript.pause_around_ms(i, i) >= config.turn_pause_ms {
+                    needs_llm = true;
+                } else if should_relabel_to_floor_holder(transcript, i, holder) {
+                    transcript.tokens[i].relabel(
+                        holder,
+                        ChangeStage::Heuristics,
+                        "apply_floor_holding",
+                        ReasonCode::JitterShortTurn,
+                        Some(floor_state.get_score(holder)),
+                    );
+                    changed_indices.push(i);
+                } else {
+                    needs_llm = true;
                 }
             }
         }
@@ -126,6 +134,7 @@ pub fn apply_floor_holding(
         tokens_relabeled: changed_indices.len(),
         changed_indices,
         needs_llm,
+        speaker_folds: vec![],
     }
 }
 
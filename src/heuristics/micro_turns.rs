@@ -1,12 +1,18 @@
-use crate::models::TokenizedTranscript;
+use crate::models::{ChangeStage, ReasonCode, TokenizedTranscript};
 
 use super::HeuristicsResult;
 
 /// Collapse micro-turns: turns <300ms surrounded by the same speaker
 ///
 /// If a very short turn is sandwiched between tokens from the same speaker,
-/// it's likely a diarization error and should be relabeled.
-pub fn collapse_micro_turns(transcript: &mut TokenizedTranscript, max_duration_ms: u64) -> HeuristicsResult {
+/// it's likely a diarization error and should be relabeled. A turn set apart
+/// by a pause of `turn_pause_ms` may be a real interjection and is left for
+/// the LLM.
+pub fn collapse_micro_turns(
+    transcript: &mut TokenizedTranscript,
+    max_duration_ms: u64,
+    turn_pause_ms: u64,
+) -> HeuristicsResult {
     let mut changed_indices = Vec::new();
     let mut needs_llm = false;
 
@@ -35,13 +41,29 @@ pub fn collapse_micro_turns(transcript: &mut TokenizedTranscript, max_duration_m
             None
         };
 
+        let (first, last) = match (turn.token_indices.first(), turn.token_indices.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => continue,
+        };
+        let paused = transcript.pause_around_ms(first, last) >= turn_pause_ms;
+
         // If surrounded by same speaker, relabel
         if let (Some(before), Some(after)) = (before_speaker, after_speaker) {
-            if before == after {
+            if before == after && paused {
+                // Set apart by a pause - may be a real turn, need LLM to decide
+                needs_llm = true;
+            } else if before == after {
                 // Relabel all tokens in this turn to the surrounding speaker
                 for &token_idx in &turn.token_indices {
-                    if transcript.tokens[token_idx].speaker != before {
-                        transcript.tokens[token_idx].speaker = before;
+                    let token = &transcript.tokens[token_idx];
+                    if token.speaker != before && !token.locked {
+                        transcript.tokens[token_idx].relabel(
+                            before,
+                            ChangeStage::Heuristics,
+                            "collapse_micro_turns",
+                            ReasonCode::JitterShortTurn,
+                            None,
+                        );
                         changed_indices.push(token_idx);
                     }
                 }
@@ -61,6 +83,7 @@ pub fn collapse_micro_turns(transcript: &mut TokenizedTranscript, max_duration_m
         tokens_relabeled: changed_indices.len(),
         changed_indices,
         needs_llm,
+        speaker_folds: vec![],
     }
 }
 
@@ -93,6 +116,7 @@ pub fn rebuild_turns(transcript: &mut TokenizedTranscript) {
                 start_ms: current_turn_start_ms,
                 end_ms: prev_end_ms,
                 token_indices: (current_turn_start_index..i).collect(),
+                pause_before_ms: transcript.tokens[current_turn_start_index].pause_before_ms,
             });
             current_turn_id += 1;
             current_speaker = token.speaker;
@@ -109,6 +133,7 @@ pub fn rebuild_turns(transcript: &mut TokenizedTranscript) {
         start_ms: current_turn_start_ms,
         end_ms: last_end_ms,
         token_indices: (current_turn_start_index..transcript.tokens.len()).collect(),
+        pause_before_ms: transcript.tokens[current_turn_start_index].pause_before_ms,
     });
 
     // Second pass: update turn_ids on tokens
@@ -146,10 +171,24 @@ mod tests {
         }"#;
 
         let mut transcript = parse_deepgram_json(json).unwrap();
-        let result = collapse_micro_turns(&mut transcript, 300);
+        let mut locked = transcript.clone();
+        let mut paused = transcript.clone();
+        let result = collapse_micro_turns(&mut transcript, 300, 1_000);
 
         // The "yes" token should have been relabeled to speaker 0
         assert_eq!(result.tokens_relabeled, 1);
         assert_eq!(transcript.tokens[2].speaker, 0);
+
+        // Unless a reviewer confirmed it
+        locked.tokens[2].locked = true;
+        let result = collapse_micro_turns(&mut locked, 300, 1_000);
+        assert_eq!(result.tokens_relabeled, 0);
+        assert_eq!(locked.tokens[2].speaker, 1);
+
+        // Or it follows a long pause
+        paused.tokens[2].pause_before_ms = 1_200;
+        let result = collapse_micro_turns(&mut paused, 300, 1_000);
+        assert_eq!(result.tokens_relabeled, 0);
+        assert!(result.needs_llm);
     }
 }
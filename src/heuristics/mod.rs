@@ -1,12 +1,14 @@
 pub mod backchannels;
 pub mod floor_holding;
 pub mod micro_turns;
+pub mod phantom_speakers;
 
 pub use backchannels::*;
 pub use floor_holding::*;
 pub use micro_turns::*;
+pub use phantom_speakers::*;
 
-use crate::models::TokenizedTranscript;
+use crate::models::{PhantomSpeakerConfig, TokenizedTranscript};
 
 /// Configuration for all heuristics
 #[derive(Debug, Clone)]
@@ -19,6 +21,18 @@ pub struct HeuristicsConfig {
     pub floor_decay_per_second: f64,
     /// Minimum floor score to consider a speaker as holding the floor
     pub min_floor_score: f64,
+    /// A short turn or floor flip separated from its neighbours by a pause
+    /// this long is a plausible speaker change; it is left for the LLM
+    pub turn_pause_ms: u64,
+    /// Thresholds for phantom speakers, folded into a real speaker
+    pub phantom: PhantomSpeakerConfig,
+    /// Number of real speakers, if known; smaller speakers beyond this are
+    /// folded into the others
+    pub expected_speakers: Option<usize>,
+    /// Share of all talk time (0.0-1.0) from which a speaker beyond
+    /// `expected_speakers` is too large to fold; speaker merge can still
+    /// join it to another speaker
+    pub max_forced_fold_share: f64,
 }
 
 impl Default for HeuristicsConfig {
@@ -45,6 +59,10 @@ impl Default for HeuristicsConfig {
             ],
             floor_decay_per_second: 0.2,
             min_floor_score: 0.3,
+            turn_pause_ms: 1_000,
+            phantom: PhantomSpeakerConfig::default(),
+            expected_speakers: None,
+            max_forced_fold_share: 0.2,
         }
     }
 }
@@ -58,29 +76,37 @@ pub struct HeuristicsResult {
     pub changed_indices: Vec<usize>,
     /// Whether more processing is needed (heuristics disagreed or low confidence)
     pub needs_llm: bool,
+    /// Speaker labels folded into other speakers
+    pub speaker_folds: Vec<SpeakerFold>,
 }
 
 /// Apply all deterministic heuristics to the transcript
 ///
 /// This runs cheap fixes before calling the LLM:
-/// 1. Collapse micro-turns (<300ms surrounded by same speaker)
-/// 2. Apply backchannel rules (single-word acknowledgements)
-/// 3. Use floor-holding model to resolve ambiguous cases
+/// 1. Fold phantom speakers into the most similar real speaker
+/// 2. Collapse micro-turns (<300ms surrounded by same speaker)
+/// 3. Apply backchannel rules (single-word acknowledgements)
+/// 4. Use floor-holding model to resolve ambiguous cases
 pub fn apply_heuristics(
     transcript: &mut TokenizedTranscript,
     config: &HeuristicsConfig,
 ) -> HeuristicsResult {
     let mut total_changed = Vec::new();
 
-    // 1. Collapse micro-turns
-    let micro_result = collapse_micro_turns(transcript, config.micro_turn_max_ms);
+    // 1. Fold phantom speakers
+    let phantom_result = fold_phantom_speakers(transcript, config);
+    total_changed.extend(phantom_result.changed_indices.clone());
+
+    // 2. Collapse micro-turns
+    let micro_result =
+        collapse_micro_turns(transcript, config.micro_turn_max_ms, config.turn_pause_ms);
     total_changed.extend(micro_result.changed_indices.clone());
 
-    // 2. Apply backchannel rules
+    // 3. Apply backchannel rules
     let backchannel_result = apply_backchannel_rules(transcript, &config.backchannel_words);
     total_changed.extend(backchannel_result.changed_indices.clone());
 
-    // 3. Apply floor-holding model
+    // 4. Apply floor-holding model
     let floor_result = apply_floor_holding(transcript, config);
     total_changed.extend(floor_result.changed_indices.clone());
 
@@ -95,5 +121,6 @@ pub fn apply_heuristics(
         tokens_relabeled: total_changed.len(),
         changed_indices: total_changed,
         needs_llm,
+        speaker_folds: phantom_result.speaker_folds,
     }
 }
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::models::{ChangeStage, ReasonCode, SpeakerActivity, TokenizedTranscript};
+
+use super::micro_turns::rebuild_turns;
+use super::{HeuristicsConfig, HeuristicsResult};
+
+/// A speaker label folded into another speaker
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerFold {
+    pub from_speaker: u32,
+    pub to_speaker: u32,
+    /// Similarity of the two speakers (0.0-1.0)
+    pub similarity: f64,
+    /// Tokens relabeled (locked tokens keep their label)
+    pub tokens_relabeled: usize,
+}
+
+/// Fold phantom speakers into the most similar real speaker
+///
+/// Speakers matching the phantom thresholds are folded. With
+/// `expected_speakers`, the speakers with the least talk time are also folded
+/// until only the expected number remain, unless their share of talk time
+/// reaches `max_forced_fold_share`: a large speaker is more likely a real
+/// person than a diarizer split, so it is kept.
+pub fn fold_phantom_speakers(
+    transcript: &mut TokenizedTranscript,
+    config: &HeuristicsConfig,
+) -> HeuristicsResult {
+    let activity = SpeakerActivity::from_transcript(transcript);
+    let mut phantoms = config.phantom.phantom_speakers(&activity);
+    if let Some(expected) = config.expected_speakers.filter(|&n| n > 0) {
+        let total_talk_ms = activity.iter().map(|a| a.talk_ms).sum::<u64>().max(1);
+        let mut by_talk = activity.clone();
+        by_talk.sort_by_key(|a| a.talk_ms);
+        let excess = activity.len().saturating_sub(expected);
+        for a in by_talk.iter().take(excess) {
+            let share = a.talk_ms as f64 / total_talk_ms as f64;
+            if share < config.max_forced_fold_share && !phantoms.contains(&a.speaker) {
+                phantoms.push(a.speaker);
+            }
+        }
+    }
+    let real: Vec<u32> = activity
+        .iter()
+        .map(|a| a.speaker)
+        .filter(|s| !phantoms.contains(s))
+        .collect();
+
+    let mut changed_indices = Vec::new();
+    let mut speaker_folds = Vec::new();
+    for &phantom in &phantoms {
+        let Some((target, similarity)) = real
+            .iter()
+            .map(|&s| (s, speaker_similarity(transcript, phantom, s)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            break;
+        };
+
+        let mut tokens_relabeled = 0;
+        for (i, token) in transcript.tokens.iter_mut().enumerate() {
+            if token.speaker == phantom && !token.locked {
+                token.relabel(
+                    target,
+                    ChangeStage::Heuristics,
+                    "fold_phantom_speakers",
+                    ReasonCode::SpeakerMerge,
+                    Some(similarity),
+                );
+                changed_indices.push(i);
+                tokens_relabeled += 1;
+            }
+        }
+        speaker_folds.push(SpeakerFold {
+            from_speaker: phantom,
+            to_speaker: target,
+            similarity,
+            tokens_relabeled,
+        });
+    }
+
+    if !changed_indices.is_empty() {
+        rebuild_turns(transcript);
+        let tokens = &transcript.tokens;
+        transcript
+            .speakers
+            .retain(|&s| tokens.iter().any(|t| t.speaker == s));
+    }
+
+    HeuristicsResult {
+        tokens_relabeled: changed_indices.len(),
+        changed_indices,
+        needs_llm: false,
+        speaker_folds,
+    }
+}
+
+/// How much speaker `a` resembles speaker `b` (0.0-1.0)
+///
+/// The mean of the share of `a`'s turns directly next to a turn of `b` and
+/// the cosine similarity of their word counts.
+fn speaker_similarity(transcript: &TokenizedTranscript, a: u32, b: u32) -> f64 {
+    let turns = &transcript.turns;
+    let a_turns: Vec<usize> = (0..turns.len()).filter(|&i| turns[i].speaker == a).collect();
+    let adjacent = a_turns
+        .iter()
+        .filter(|&&i| {
+            (i > 0 && turns[i - 1].speaker == b) || turns.get(i + 1).is_some_and(|t| t.speaker == b)
+        })
+        .count();
+    let adjacency = adjacent as f64 / a_turns.len().max(1) as f64;
+
+    (adjacency + cosine_similarity(&word_counts(transcript, a), &word_counts(transcript, b))) / 2.0
+}
+
+pub(crate) fn word_counts(transcript: &TokenizedTranscript, speaker: u32) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for token in transcript.tokens.iter().filter(|t| t.speaker == speaker) {
+        *counts.entry(token.word.to_lowercase()).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+pub(crate) fn cosine_similarity(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(w, x)| b.get(w).map(|y| x * y)).sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if dot == 0.0 || denominator == 0.0 {
+        return 0.0;
+    }
+    dot / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+
+    #[test]
+    fn test_fold_phantom_speakers() {
+        // Speakers 0 and 1 alternate; speaker 2 appears for one word
+        let mut words: Vec<String> = (0..40)
+            .map(|i| {
+                format!(
+                    r#"{{"word": "w{}", "start": {}.0, "end": {}.8, "confidence": 0.9, "speaker": {}}}"#,
+                    i % 5,
+                    i,
+                    i,
+                    (i / 10) % 2
+                )
+            })
+            .collect();
+        words[15] = r#"{"word": "w0", "start": 15.0, "end": 15.4, "confidence": 0.9, "speaker": 2}"#
+            .to_string();
+        let json = format!(
+            r#"{{"results": {{"channels": [{{"alternatives": [{{"words": [{}]}}]}}]}}}}"#,
+            words.join(",")
+        );
+        let transcript = parse_deepgram_json(&json).unwrap();
+
+        let mut folded = transcript.clone();
+        let result = fold_phantom_speakers(&mut folded, &HeuristicsConfig::default());
+        assert_eq!(result.tokens_relabeled, 1);
+        assert_eq!(result.speaker_folds[0].from_speaker, 2);
+        assert_eq!(result.speaker_folds[0].to_speaker, 1);
+        assert_eq!(folded.speakers, vec![0, 1]);
+
+        // With one expected speaker, a small real speaker would be folded
+        // too, but speaker 1 has half the talk time
+        let config = HeuristicsConfig {
+            expected_speakers: Some(1),
+            ..Default::default()
+        };
+        let mut folded = transcript;
+        let result = fold_phantom_speakers(&mut folded, &config);
+        assert_eq!(result.speaker_folds.len(), 1);
+        assert_eq!(folded.speakers, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_expected_speakers_keeps_large_distinct_speaker() {
+        // Speakers 0 and 1 share a vocabulary; speaker 2 talks for three
+        // turns in words of its own, too long to be a phantom
+        let words: Vec<String> = (0..60)
+            .map(|i| {
+                let (word, speaker) = match i {
+                    5 | 20..=29 | 45..=49 => (format!("x{}", i % 7), 2),
+                    _ => (format!("w{}", i % 5), (i / 5) % 2),
+                };
+                format!(
+                    r#"{{"word": "{}", "start": {}.0, "end": {}.8, "confidence": 0.9, "speaker": {}}}"#,
+                    word, i, i, speaker
+                )
+            })
+            .collect();
+        let json = format!(
+            r#"{{"results": {{"channels": [{{"alternatives": [{{"words": [{}]}}]}}]}}}}"#,
+            words.join(",")
+        );
+        let transcript = parse_deepgram_json(&json).unwrap();
+
+        let config = HeuristicsConfig {
+            expected_speakers: Some(2),
+            ..Default::default()
+        };
+
+        // Speaker 2 is the smallest but holds over a quarter of the talk
+        // time, so it is kept
+        let mut folded = transcript.clone();
+        let result = fold_phantom_speakers(&mut folded, &config);
+        assert!(result.speaker_folds.is_empty());
+        assert_eq!(folded.speakers, vec![0, 1, 2]);
+
+        // Raising the share limit folds it as before
+        let config = HeuristicsConfig {
+            max_forced_fold_share: 1.0,
+            ..config
+        };
+        let mut folded = transcript;
+        let result = fold_phantom_speakers(&mut folded, &config);
+        assert_eq!(result.speaker_folds.len(), 1);
+        assert_eq!(result.speaker_folds[0].from_speaker, 2);
+    }
+}
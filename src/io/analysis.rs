@@ -0,0 +1,237 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::models::{ProblemType, TokenizedTranscript, WindowSet};
+use crate::stages::{MergedZone, NormalizationResult, Pause, ProblemZone};
+
+use super::analytics::TranscriptAnalytics;
+use super::diff::snake_case;
+use super::output::format_timestamp;
+
+/// Output format for the analyze command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnalysisFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for AnalysisFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => bail!("Unknown analysis format {:?} (expected text or json)", s),
+        }
+    }
+}
+
+/// Everything the analyze command reports about a transcript
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptAnalysis {
+    pub total_tokens: usize,
+    pub total_turns: usize,
+    pub speakers: Vec<u32>,
+    pub duration_ms: u64,
+    /// Tokens overlapping speech from another speaker
+    pub overlapped_tokens: usize,
+    /// Tokens at a quick speaker change without overlap
+    pub tight_handoff_tokens: usize,
+    /// Every detected problem zone
+    pub problem_zones: Vec<ProblemZone>,
+    /// Problem zones unioned into regions, with their problem types and score
+    pub merged_zones: Vec<MergedZone>,
+    /// Every pause between words, with likely turn-taking points flagged
+    pub pauses: Vec<Pause>,
+    /// Every window, flagged if it would be sent to the LLM
+    pub windows: Vec<WindowSummary>,
+    pub problem_window_count: usize,
+    /// Editable tokens across problem windows (what Stage 1 would send)
+    pub problem_window_tokens: usize,
+    pub speaker_stats: TranscriptAnalytics,
+}
+
+/// A window without its token lists
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowSummary {
+    pub window_id: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub token_count: usize,
+    pub anchor_token_count: usize,
+    /// Whether the window would be processed by the LLM
+    pub is_problem_window: bool,
+    pub problem_types: Vec<ProblemType>,
+    pub severity: f64,
+}
+
+impl TranscriptAnalysis {
+    /// Build from a normalized transcript
+    pub fn new(transcript: &TokenizedTranscript, normalization: &NormalizationResult) -> Self {
+        let windows = summarize_windows(&normalization.windows);
+        let problem_window_tokens = windows
+            .iter()
+            .filter(|w| w.is_problem_window)
+            .map(|w| w.token_count)
+            .sum();
+
+        Self {
+            total_tokens: transcript.tokens.len(),
+            total_turns: transcript.turns.len(),
+            speakers: transcript.speakers.clone(),
+            duration_ms: transcript.duration_ms(),
+            overlapped_tokens: transcript.tokens.iter().filter(|t| t.is_overlap_region).count(),
+            tight_handoff_tokens: transcript.tokens.iter().filter(|t| t.is_tight_handoff).count(),
+            problem_zones: normalization.problem_zones.clone(),
+            merged_zones: normalization.merged_zones.clone(),
+            pauses: normalization.pauses.clone(),
+            problem_window_count: normalization.windows.problem_window_count(),
+            problem_window_tokens,
+            windows,
+            speaker_stats: TranscriptAnalytics::from_transcript(transcript, None),
+        }
+    }
+
+    /// Number of problem zones of a given type
+    pub fn zone_count(&self, problem_type: ProblemType) -> usize {
+        self.problem_zones
+            .iter()
+            .filter(|z| z.problem_type == problem_type)
+            .count()
+    }
+
+    /// Format as a text report
+    pub fn format_text(&self) -> String {
+        let mut output = String::from("Transcript Analysis\n==================\n");
+        output.push_str(&format!("Total tokens: {}\n", self.total_tokens));
+        output.push_str(&format!("Total turns: {}\n", self.total_turns));
+        output.push_str(&format!("Speakers: {:?}\n", self.speakers));
+        output.push_str(&format!("Duration: {:.1}s\n", self.duration_ms as f64 / 1000.0));
+        output.push_str(&format!("Overlapped speech tokens: {}\n", self.overlapped_tokens));
+        output.push_str(&format!("Tight handoff tokens: {}\n\n", self.tight_handoff_tokens));
+
+        output.push_str("Problem Zones\n-------------\n");
+        let counts = [
+            ("Speaker jitter", ProblemType::SpeakerJitter),
+            ("Short turn", ProblemType::ShortTurn),
+            ("Overlap-adjacent", ProblemType::OverlapAdjacent),
+            ("Handoff-adjacent", ProblemType::HandoffAdjacent),
+            ("Low confidence", ProblemType::LowConfidence),
+            ("Phantom speaker", ProblemType::PhantomSpeaker),
+            ("Low transcription confidence", ProblemType::LowTranscriptionConfidence),
+        ];
+        for (label, problem_type) in counts {
+            output.push_str(&format!("{} zones: {}\n", label, self.zone_count(problem_type)));
+        }
+        output.push_str(&format!("Merged problem regions: {}\n", self.merged_zones.len()));
+        let mut regions: Vec<&MergedZone> = self.merged_zones.iter().collect();
+        regions.sort_by(|a, b| b.score.total_cmp(&a.score));
+        for region in regions.iter().take(5) {
+            output.push_str(&format!(
+                "  {}-{}: {} zones, score {:.2} ({})\n",
+                format_timestamp(region.start_ms),
+                format_timestamp(region.end_ms),
+                region.zone_count,
+                region.score,
+                region
+                    .problem_types
+                    .iter()
+                    .map(snake_case)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        output.push('\n');
+
+        output.push_str("Pauses\n------\n");
+        output.push_str(&format!("Pauses: {}\n", self.pauses.len()));
+        output.push_str(&format!(
+            "Likely turn-taking points: {}\n",
+            self.pauses.iter().filter(|p| p.is_turn_point).count()
+        ));
+        let longest = self.pauses.iter().map(Pause::duration_ms).max().unwrap_or(0);
+        output.push_str(&format!("Longest pause: {}ms\n\n", longest));
+
+        output.push_str("Windows\n-------\n");
+        output.push_str(&format!("Total windows: {}\n", self.windows.len()));
+        output.push_str(&format!("Problem windows: {}\n", self.problem_window_count));
+        output.push_str(&format!("Problem window tokens: {}\n\n", self.problem_window_tokens));
+
+        output.push_str(&self.speaker_stats.format_text());
+        output
+    }
+
+    /// Format as pretty-printed JSON
+    pub fn format_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize analysis")
+    }
+
+    /// Format in the given output format
+    pub fn format(&self, format: AnalysisFormat) -> Result<String> {
+        match format {
+            AnalysisFormat::Text => Ok(self.format_text()),
+            AnalysisFormat::Json => self.format_json(),
+        }
+    }
+}
+
+fn summarize_windows(windows: &WindowSet) -> Vec<WindowSummary> {
+    windows
+        .windows
+        .iter()
+        .enumerate()
+        .map(|(i, w)| WindowSummary {
+            window_id: w.window_id.clone(),
+            start_ms: w.start_ms,
+            end_ms: w.end_ms,
+            token_count: w.token_count(),
+            anchor_token_count: w.anchor_prefix_indices.len() + w.anchor_suffix_indices.len(),
+            is_problem_window: windows.problem_window_indices.contains(&i),
+            problem_types: w.problem_types.clone(),
+            severity: w.severity,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+    use crate::models::{ProblemZoneConfig, WindowConfig};
+    use crate::stages::normalize;
+
+    #[test]
+    fn test_analysis_json_lists_zones_and_windows() {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "yeah", "start": 0.4, "end": 0.6, "confidence": 0.95, "speaker": 1, "speaker_confidence": 0.4},
+                            {"word": "right", "start": 0.7, "end": 1.0, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let normalization =
+            normalize(&mut transcript, &WindowConfig::default(), &ProblemZoneConfig::default());
+        let analysis = TranscriptAnalysis::new(&transcript, &normalization);
+
+        assert!(analysis.zone_count(ProblemType::LowConfidence) > 0);
+        assert_eq!(analysis.problem_window_count, 1);
+
+        let value: serde_json::Value =
+            serde_json::from_str(&analysis.format(AnalysisFormat::Json).unwrap()).unwrap();
+        assert_eq!(value["windows"][0]["is_problem_window"], true);
+        assert!(value["problem_zones"][0]["token_indices"].is_array());
+        assert!(value["problem_zones"][0]["problem_type"].is_string());
+        assert!(value["merged_zones"][0]["problem_types"].is_array());
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::models::TokenizedTranscript;
+
+use super::output::{format_timestamp, HumanRenderOptions};
+
+/// Talk-time and turn-taking statistics for a transcript
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptAnalytics {
+    pub duration_ms: u64,
+    pub total_words: usize,
+    pub total_turns: usize,
+    /// Per-speaker statistics, in speaker ID order
+    pub speakers: Vec<SpeakerStats>,
+    /// Speech time per speaker for each minute of the recording
+    pub timeline: Vec<MinuteActivity>,
+    /// How minute start times are written in the text report
+    #[serde(skip)]
+    options: HumanRenderOptions,
+}
+
+/// Statistics for a single speaker
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerStats {
+    pub speaker: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub words: usize,
+    pub turns: usize,
+    /// Sum of turn durations
+    pub talk_time_ms: u64,
+    /// Fraction of all speakers' talk time (0.0-1.0)
+    pub talk_share: f64,
+    /// Words per minute of talk time
+    pub words_per_minute: f64,
+    /// Speech time of the longest single turn: the sum of its word
+    /// durations, so pauses inside the turn don't count
+    pub longest_monologue_ms: u64,
+    /// Start of the turn with the longest speech time
+    pub longest_monologue_start_ms: u64,
+    /// Turns this speaker started in overlapped speech or at a tight handoff
+    pub interruptions: usize,
+    /// Turns of this speaker cut off by an interruption
+    pub interrupted: usize,
+    /// Mean gap between another speaker's turn and this speaker's reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_response_latency_ms: Option<f64>,
+    /// Words ending in a question mark
+    pub questions: usize,
+    /// Mean speaker confidence over words whose confidence is known
+    /// (diarizer or estimated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_speaker_confidence: Option<f64>,
+}
+
+/// Speech time per speaker within one minute
+#[derive(Debug, Clone, Serialize)]
+pub struct MinuteActivity {
+    pub minute: u64,
+    /// Speaker ID -> milliseconds of word audio starting in this minute
+    pub talk_ms: BTreeMap<u32, u64>,
+}
+
+impl TranscriptAnalytics {
+    /// Compute analytics for a transcript
+    ///
+    /// Interruptions are counted from `is_overlap_region` and
+    /// `is_tight_handoff`, so the transcript should have been through
+    /// `normalize` first.
+    pub fn from_transcript(
+        transcript: &TokenizedTranscript,
+        speaker_names: Option<&HashMap<u32, String>>,
+    ) -> Self {
+        let mut stats: BTreeMap<u32, SpeakerStats> = transcript
+            .speakers
+            .iter()
+            .map(|&speaker| (speaker, SpeakerStats::new(speaker, speaker_names)))
+            .collect();
+        let mut latencies: HashMap<u32, Vec<u64>> = HashMap::new();
+        let mut confidences: HashMap<u32, Vec<f64>> = HashMap::new();
+
+        for token in &transcript.tokens {
+            let s = stats
+                .entry(token.speaker)
+                .or_insert_with(|| SpeakerStats::new(token.speaker, speaker_names));
+            s.words += 1;
+            if let Some(conf) = token.speaker_confidence() {
+                confidences.entry(token.speaker).or_default().push(conf);
+            }
+            if token.punctuated_word.as_deref().unwrap_or(&token.word).ends_with('?') {
+                s.questions += 1;
+            }
+        }
+
+        for (i, turn) in transcript.turns.iter().enumerate() {
+            let s = stats
+                .entry(turn.speaker)
+                .or_insert_with(|| SpeakerStats::new(turn.speaker, speaker_names));
+            s.turns += 1;
+            s.talk_time_ms += turn.duration_ms();
+            let speech_ms: u64 = turn
+                .token_indices
+                .iter()
+                .filter_map(|&t| transcript.tokens.get(t))
+                .map(|t| t.end_ms.saturating_sub(t.start_ms))
+                .sum();
+            if speech_ms > s.longest_monologue_ms {
+                s.longest_monologue_ms = speech_ms;
+                s.longest_monologue_start_ms = turn.start_ms;
+            }
+
+            let Some(prev) = i.checked_sub(1).map(|p| &transcript.turns[p]) else {
+                continue;
+            };
+            if prev.speaker == turn.speaker {
+                continue;
+            }
+
+            latencies
+                .entry(turn.speaker)
+                .or_default()
+                .push(turn.start_ms.saturating_sub(prev.end_ms));
+
+            // Overlapped speech and tight handoffs both cut into the previous turn
+            let interrupting = turn
+                .token_indices
+                .first()
+                .and_then(|&t| transcript.tokens.get(t))
+                .is_some_and(|t| t.is_overlap_region || t.is_tight_handoff);
+            if interrupting {
+                if let Some(s) = stats.get_mut(&turn.speaker) {
+                    s.interruptions += 1;
+                }
+                if let Some(p) = stats.get_mut(&prev.speaker) {
+                    p.interrupted += 1;
+                }
+            }
+        }
+
+        let total_talk_ms: u64 = stats.values().map(|s| s.talk_time_ms).sum();
+        for s in stats.values_mut() {
+            if total_talk_ms > 0 {
+                s.talk_share = s.talk_time_ms as f64 / total_talk_ms as f64;
+            }
+            if s.talk_time_ms > 0 {
+                s.words_per_minute = s.words as f64 / (s.talk_time_ms as f64 / 60_000.0);
+            }
+            s.avg_response_latency_ms = latencies
+                .get(&s.speaker)
+                .filter(|l| !l.is_empty())
+                .map(|l| l.iter().sum::<u64>() as f64 / l.len() as f64);
+            s.avg_speaker_confidence = confidences
+                .get(&s.speaker)
+                .filter(|c| !c.is_empty())
+                .map(|c| c.iter().sum::<f64>() / c.len() as f64);
+        }
+
+        Self {
+            duration_ms: transcript.duration_ms(),
+            total_words: transcript.tokens.len(),
+            total_turns: transcript.turns.len(),
+            speakers: stats.into_values().collect(),
+            timeline: minute_timeline(transcript),
+            options: HumanRenderOptions::default(),
+        }
+    }
+
+    /// Render minute start times with the offset and recording start of `options`
+    pub fn with_options(mut self, options: HumanRenderOptions) -> Self {
+        let last_minute_ms = self.timeline.last().map_or(0, |m| m.minute * 60_000);
+        self.options = options.spanning(last_minute_ms);
+        self
+    }
+
+    /// Format as a text report
+    pub fn format_text(&self) -> String {
+        let mut output = String::from("Speaker Analytics\n=================\n");
+        output.push_str(&format!(
+            "Duration: {}, {} words, {} turns\n\n",
+            format_timestamp(self.duration_ms),
+            self.total_words,
+            self.total_turns
+        ));
+
+        let labels: Vec<String> = self.speakers.iter().map(SpeakerStats::label).collect();
+        let label_width = labels.iter().map(String::len).max().unwrap_or(0).max(7);
+
+        output.push_str(&format!(
+            "{:<lw$}  {:>11}  {:>6}  {:>5}  {:>11}  {:>10}  {:>11}  {:>9}  {:>9}\n",
+            "Speaker",
+            "Talk time",
+            "Share",
+            "WPM",
+            "Longest",
+            "Interrupts",
+            "Interrupted",
+            "Latency",
+            "Questions",
+            lw = label_width
+        ));
+        for (s, label) in self.speakers.iter().zip(&labels) {
+            let latency = s
+                .avg_response_latency_ms
+                .map(|l| format!("{:.0}ms", l))
+                .unwrap_or_else(|| "-".to_string());
+            output.push_str(&format!(
+                "{:<lw$}  {:>11}  {:>5.1}%  {:>5.0}  {:>11}  {:>10}  {:>11}  {:>9}  {:>9}\n",
+                label,
+                format_timestamp(s.talk_time_ms),
+                s.talk_share * 100.0,
+                s.words_per_minute,
+                format_timestamp(s.longest_monologue_ms),
+                s.interruptions,
+                s.interrupted,
+                latency,
+                s.questions,
+                lw = label_width
+            ));
+        }
+
+        output.push_str("\nActivity per minute (seconds of speech)\n");
+        let starts: Vec<String> = self
+            .timeline
+            .iter()
+            .map(|m| self.options.timestamp(m.minute * 60_000))
+            .collect();
+        let start_width = starts.iter().map(String::len).max().unwrap_or(0).max(5);
+        output.push_str(&format!("{:>sw$}", "Start", sw = start_width));
+        for label in &labels {
+            output.push_str(&format!("  {:>w$}", label, w = label.len().max(5)));
+        }
+        output.push('\n');
+        for (minute, start) in self.timeline.iter().zip(&starts) {
+            output.push_str(&format!("{:>sw$}", start, sw = start_width));
+            for (s, label) in self.speakers.iter().zip(&labels) {
+                let ms = minute.talk_ms.get(&s.speaker).copied().unwrap_or(0);
+                output.push_str(&format!(
+                    "  {:>w$.1}",
+                    ms as f64 / 1000.0,
+                    w = label.len().max(5)
+                ));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Write to a JSON file
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        serde_json::to_writer_pretty(file, self).context("Failed to write JSON")?;
+        Ok(())
+    }
+
+    /// Write as JSON if the path ends in `.json`, otherwise as text
+    pub fn write_file(&self, path: &Path) -> Result<()> {
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            return self.write_json(path);
+        }
+
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        write!(file, "{}", self.format_text())?;
+        Ok(())
+    }
+}
+
+impl SpeakerStats {
+    fn new(speaker: u32, speaker_names: Option<&HashMap<u32, String>>) -> Self {
+        Self {
+            speaker,
+            name: speaker_names.and_then(|names| names.get(&speaker).cloned()),
+            words: 0,
+            turns: 0,
+            talk_time_ms: 0,
+            talk_share: 0.0,
+            words_per_minute: 0.0,
+            longest_monologue_ms: 0,
+            longest_monologue_start_ms: 0,
+            interruptions: 0,
+            interrupted: 0,
+            avg_response_latency_ms: None,
+            questions: 0,
+            avg_speaker_confidence: None,
+        }
+    }
+
+    fn label(&self) -> String {
+        self.name.clone().unwrap_or_else(|| format!("Speaker {}", self.speaker))
+    }
+}
+
+/// Bucket word durations by the minute each word starts in
+fn minute_timeline(transcript: &TokenizedTranscript) -> Vec<MinuteActivity> {
+    // Buckets are absolute recording minutes, so size them from the last end
+    let last_end_ms = transcript.tokens.iter().map(|t| t.end_ms).max().unwrap_or(0);
+    let minutes = last_end_ms.div_ceil(60_000);
+    let mut timeline: Vec<MinuteActivity> = (0..minutes)
+        .map(|minute| MinuteActivity {
+            minute,
+            talk_ms: BTreeMap::new(),
+        })
+        .collect();
+
+    for token in &transcript.tokens {
+        if let Some(bucket) = timeline.get_mut((token.start_ms / 60_000) as usize) {
+            *bucket.talk_ms.entry(token.speaker).or_insert(0) += token.duration_ms();
+        }
+    }
+
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+
+    #[test]
+    fn test_speaker_analytics() {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "ready", "start": 0.0, "end": 0.5, "confidence": 0.95, "speaker": 0, "punctuated_word": "Ready?"},
+                            {"word": "yes", "start": 1.0, "end": 1.5, "confidence": 0.95, "speaker": 1},
+                            {"word": "let's", "start": 1.5, "end": 1.8, "confidence": 0.95, "speaker": 0},
+                            {"word": "go", "start": 61.0, "end": 61.5, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        transcript.tokens[2].is_overlap_region = true;
+
+        let analytics = TranscriptAnalytics::from_transcript(&transcript, None);
+        let s0 = &analytics.speakers[0];
+        let s1 = &analytics.speakers[1];
+
+        assert_eq!(s0.words, 3);
+        assert_eq!(s0.questions, 1);
+        assert_eq!(s0.interruptions, 1);
+        assert_eq!(s1.interrupted, 1);
+        assert_eq!(s1.avg_response_latency_ms, Some(500.0));
+        // The minute of silence inside the last turn is not speech
+        assert_eq!(s0.longest_monologue_ms, 800);
+        assert_eq!(s0.longest_monologue_start_ms, 1500);
+        assert_eq!(analytics.timeline.len(), 2);
+        assert_eq!(analytics.timeline[1].talk_ms.get(&0), Some(&500));
+        assert!(analytics.format_text().contains("Speaker 1"));
+    }
+
+    #[test]
+    fn test_minute_timeline_late_start() {
+        // Speech starts 50s in; the last word falls in the third minute
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "hello", "start": 50.0, "end": 50.5, "confidence": 0.95, "speaker": 0},
+                            {"word": "bye", "start": 125.0, "end": 125.5, "confidence": 0.95, "speaker": 1}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let transcript = parse_deepgram_json(json).unwrap();
+
+        let timeline = minute_timeline(&transcript);
+
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline[0].talk_ms.get(&0), Some(&500));
+        assert_eq!(timeline[2].talk_ms.get(&1), Some(&500));
+    }
+}
@@ -0,0 +1,311 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::heuristics::micro_turns::rebuild_turns;
+use crate::models::{ChangeStage, ReasonCode, Token, TokenizedTranscript};
+
+use super::output::HumanRenderOptions;
+
+/// A set of proposed speaker changes that can be reviewed and applied later
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// Total number of tokens in the transcript the changes were computed on
+    pub total_tokens: usize,
+    /// Proposed changes, in token order
+    pub changes: Vec<ProposedChange>,
+}
+
+/// A single proposed speaker change
+///
+/// Token IDs are regenerated on every parse, so changes are matched back to
+/// tokens by `original_index`, `word` and `start_ms` instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedChange {
+    /// Token ID in the run that produced the change set
+    pub token_id: String,
+    /// Index of the token in the source transcript
+    pub original_index: usize,
+    /// Word text, used to verify the match
+    pub word: String,
+    /// Start timestamp in milliseconds, used to verify the match
+    pub start_ms: u64,
+    /// Speaker in the source transcript
+    pub old_speaker: u32,
+    /// Proposed speaker
+    pub new_speaker: u32,
+    /// Stage that made the final change
+    pub stage: ChangeStage,
+    /// Rule name or window ID(s) behind the final change
+    pub source: String,
+    /// Reason code for the final change
+    pub reason: ReasonCode,
+    /// Vote weight behind the final change, if it was voted on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+impl ChangeSet {
+    /// Collect every token whose speaker differs from the original
+    ///
+    /// Stage, source, reason and confidence come from the token's last
+    /// provenance entry. Fails if a relabeled token has no provenance, since
+    /// every relabel must go through `Token::relabel`.
+    pub fn from_transcript(
+        transcript: &TokenizedTranscript,
+        original_speakers: &[u32],
+    ) -> Result<Self> {
+        let mut changes = Vec::new();
+
+        for (t, &orig) in transcript.tokens.iter().zip(original_speakers.iter()) {
+            if t.speaker == orig {
+                continue;
+            }
+            let Some(last) = t.provenance.last() else {
+                bail!(
+                    "Token #{} {:?} changed from speaker {} to {} without a provenance entry",
+                    t.original_index,
+                    t.word,
+                    orig,
+                    t.speaker
+                );
+            };
+            changes.push(ProposedChange {
+                token_id: t.token_id.clone(),
+                original_index: t.original_index,
+                word: t.word.clone(),
+                start_ms: t.start_ms,
+                old_speaker: orig,
+                new_speaker: t.speaker,
+                stage: last.stage,
+                source: last.source.clone(),
+                reason: last.reason,
+                confidence: last.weight,
+            });
+        }
+
+        Ok(Self {
+            total_tokens: transcript.tokens.len(),
+            changes,
+        })
+    }
+
+    /// Number of proposed changes
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Whether there are no proposed changes
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply the changes to a freshly parsed transcript
+    ///
+    /// Fails if a change does not match a token in the transcript. Changes
+    /// whose token no longer carries `old_speaker` are skipped. Returns the
+    /// number of changes applied.
+    pub fn apply(&self, transcript: &mut TokenizedTranscript) -> Result<usize> {
+        let mut applied = 0;
+
+        for change in &self.changes {
+            let token =
+                find_token_mut(transcript, change.original_index, &change.word, change.start_ms)?;
+
+            if token.speaker != change.old_speaker {
+                warn!(
+                    "Skipping token {} ({:?}): speaker is {}, change set expects {}",
+                    change.original_index,
+                    change.word,
+                    token.speaker,
+                    change.old_speaker
+                );
+                continue;
+            }
+
+            token.relabel(
+                change.new_speaker,
+                change.stage,
+                change.source.clone(),
+                change.reason,
+                change.confidence,
+            );
+            applied += 1;
+        }
+
+        if applied > 0 {
+            rebuild_turns(transcript);
+        }
+
+        Ok(applied)
+    }
+
+    /// Format the changes as a human-readable diff
+    ///
+    /// Timestamps follow the offset and recording start of `options`.
+    pub fn format_diff(&self, options: &HumanRenderOptions) -> String {
+        let last_ms = self.changes.iter().map(|c| c.start_ms).max().unwrap_or(0);
+        let options = options.spanning(last_ms);
+        let mut output = format!(
+            "{} proposed changes ({} tokens)\n\n",
+            self.changes.len(),
+            self.total_tokens
+        );
+
+        for change in &self.changes {
+            let confidence = change
+                .confidence
+                .map(|c| format!(", weight {:.2}", c))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "[{}] #{} {:?}: Speaker {} -> Speaker {} ({:?} via {}, {:?}{})\n",
+                options.timestamp(change.start_ms),
+                change.original_index,
+                change.word,
+                change.old_speaker,
+                change.new_speaker,
+                change.stage,
+                change.source,
+                change.reason,
+                confidence
+            ));
+        }
+
+        output
+    }
+
+    /// Write to a JSON file
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        serde_json::to_writer_pretty(file, self).context("Failed to write JSON")?;
+        Ok(())
+    }
+
+    /// Read from a JSON file
+    pub fn read_json(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file: {:?}", path))?;
+        serde_json::from_str(&content).context("Failed to parse change set JSON")
+    }
+
+    /// Write the human-readable diff to a text file
+    pub fn write_diff(&self, path: &Path, options: &HumanRenderOptions) -> Result<()> {
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        write!(file, "{}", self.format_diff(options))?;
+        Ok(())
+    }
+}
+
+/// Find a token by original index, checking its word and start time
+///
+/// Token IDs are regenerated on every parse, so files that refer to tokens
+/// across runs (change sets, review decisions) match on these instead.
+pub(crate) fn find_token_mut<'a>(
+    transcript: &'a mut TokenizedTranscript,
+    original_index: usize,
+    word: &str,
+    start_ms: u64,
+) -> Result<&'a mut Token> {
+    let token = transcript
+        .tokens
+        .iter_mut()
+        .find(|t| t.original_index == original_index)
+        .with_context(|| format!("No token at index {}", original_index))?;
+
+    if token.word != word || token.start_ms != start_ms {
+        bail!(
+            "Token {} does not match: expected {:?} at {}ms, found {:?} at {}ms",
+            original_index,
+            word,
+            start_ms,
+            token.word,
+            token.start_ms
+        );
+    }
+
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+
+    const JSON: &str = r#"{
+        "results": {
+            "channels": [{
+                "alternatives": [{
+                    "words": [
+                        {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0},
+                        {"word": "yeah", "start": 0.4, "end": 0.6, "confidence": 0.95, "speaker": 1},
+                        {"word": "right", "start": 0.7, "end": 1.0, "confidence": 0.95, "speaker": 0}
+                    ]
+                }]
+            }]
+        }
+    }"#;
+
+    #[test]
+    fn test_change_set_round_trip() {
+        let mut transcript = parse_deepgram_json(JSON).unwrap();
+        let original_speakers: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
+        transcript.tokens[1].relabel(
+            0,
+            ChangeStage::Reconciliation,
+            "w_0",
+            ReasonCode::LexicalContinuity,
+            Some(0.9),
+        );
+
+        let change_set = ChangeSet::from_transcript(&transcript, &original_speakers).unwrap();
+        assert_eq!(change_set.len(), 1);
+        let diff = change_set.format_diff(&HumanRenderOptions::default());
+        assert!(diff.contains("Speaker 1 -> Speaker 0"));
+
+        // Token IDs differ between parses; matching uses index, word and time
+        let mut fresh = parse_deepgram_json(JSON).unwrap();
+        let applied = change_set.apply(&mut fresh).unwrap();
+
+        assert_eq!(applied, 1);
+        assert_eq!(fresh.tokens[1].speaker, 0);
+        assert_eq!(fresh.turns.len(), 1);
+        assert_eq!(fresh.tokens[1].provenance[0].source, "w_0");
+    }
+
+    #[test]
+    fn test_change_without_provenance_is_an_error() {
+        let mut transcript = parse_deepgram_json(JSON).unwrap();
+        let original_speakers: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
+        transcript.tokens[1].speaker = 0;
+
+        assert!(ChangeSet::from_transcript(&transcript, &original_speakers).is_err());
+    }
+
+    #[test]
+    fn test_apply_rejects_mismatched_token() {
+        let mut transcript = parse_deepgram_json(JSON).unwrap();
+        let change_set = ChangeSet {
+            total_tokens: 3,
+            changes: vec![ProposedChange {
+                token_id: "t_1".to_string(),
+                original_index: 1,
+                word: "nope".to_string(),
+                start_ms: 400,
+                old_speaker: 1,
+                new_speaker: 0,
+                stage: ChangeStage::Heuristics,
+                source: "apply_backchannel_rules".to_string(),
+                reason: ReasonCode::BackchannelAttribution,
+                confidence: None,
+            }],
+        };
+
+        assert!(change_set.apply(&mut transcript).is_err());
+        assert_eq!(transcript.tokens[1].speaker, 1);
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::models::{Token, TokenizedTranscript};
+
+use super::output::HumanRenderOptions;
+
+/// Width of each column in the text diff
+const COLUMN_WIDTH: usize = 44;
+
+/// Side-by-side view of original vs. corrected speaker attribution
+pub struct TranscriptDiff<'a> {
+    transcript: &'a TokenizedTranscript,
+    original_speakers: &'a [u32],
+    speaker_names: Option<&'a HashMap<u32, String>>,
+    options: HumanRenderOptions,
+}
+
+/// A run of tokens with a constant original and corrected speaker
+struct DiffRow {
+    start: usize,
+    end: usize,
+    original_speaker: u32,
+    corrected_speaker: u32,
+}
+
+impl DiffRow {
+    fn is_relabeled(&self) -> bool {
+        self.original_speaker != self.corrected_speaker
+    }
+}
+
+impl<'a> TranscriptDiff<'a> {
+    pub fn new(transcript: &'a TokenizedTranscript, original_speakers: &'a [u32]) -> Self {
+        Self {
+            transcript,
+            original_speakers,
+            speaker_names: None,
+            options: HumanRenderOptions::default(),
+        }
+    }
+
+    /// Use participant names for speaker labels
+    pub fn with_speaker_names(mut self, speaker_names: &'a HashMap<u32, String>) -> Self {
+        self.speaker_names = Some(speaker_names);
+        self
+    }
+
+    /// Render timestamps with the offset and recording start of `options`
+    pub fn with_options(mut self, options: HumanRenderOptions) -> Self {
+        self.options = options.spanning(self.transcript.end_ms());
+        self
+    }
+
+    /// Split tokens at every original or corrected turn boundary
+    fn rows(&self) -> Vec<DiffRow> {
+        let mut rows: Vec<DiffRow> = Vec::new();
+
+        for (i, (token, &original)) in self
+            .transcript
+            .tokens
+            .iter()
+            .zip(self.original_speakers.iter())
+            .enumerate()
+        {
+            match rows.last_mut() {
+                Some(row)
+                    if row.original_speaker == original && row.corrected_speaker == token.speaker =>
+                {
+                    row.end = i + 1;
+                }
+                _ => rows.push(DiffRow {
+                    start: i,
+                    end: i + 1,
+                    original_speaker: original,
+                    corrected_speaker: token.speaker,
+                }),
+            }
+        }
+
+        rows
+    }
+
+    fn speaker_label(&self, speaker: u32) -> String {
+        self.speaker_names
+            .and_then(|names| names.get(&speaker))
+            .cloned()
+            .unwrap_or_else(|| format!("Speaker {}", speaker))
+    }
+
+    fn tokens(&self, row: &DiffRow) -> &[Token] {
+        &self.transcript.tokens[row.start..row.end]
+    }
+
+    /// Number of relabeled tokens
+    pub fn relabeled_count(&self) -> usize {
+        self.rows()
+            .iter()
+            .filter(|r| r.is_relabeled())
+            .map(|r| r.end - r.start)
+            .sum()
+    }
+
+    /// Format as two text columns, relabeled words marked `[word]`
+    ///
+    /// Each relabeled run is followed by one annotation line per token with
+    /// the stage, source and reason code of every change it went through.
+    pub fn format_text(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!(
+            "{:<width$} | {}\n",
+            "ORIGINAL",
+            "CORRECTED",
+            width = COLUMN_WIDTH
+        ));
+        output.push_str(&format!("{}-+-{}\n", "-".repeat(COLUMN_WIDTH), "-".repeat(COLUMN_WIDTH)));
+
+        let mut last_original = None;
+        let mut last_corrected = None;
+
+        for row in self.rows() {
+            let tokens = self.tokens(&row);
+            let start_ms = tokens[0].start_ms;
+            let mut left = Vec::new();
+            let mut right = Vec::new();
+
+            if last_original != Some(row.original_speaker) {
+                left.push(format!(
+                    "[{}] {}:",
+                    self.options.timestamp(start_ms),
+                    self.speaker_label(row.original_speaker)
+                ));
+                last_original = Some(row.original_speaker);
+            }
+            if last_corrected != Some(row.corrected_speaker) {
+                right.push(format!(
+                    "[{}] {}:",
+                    self.options.timestamp(start_ms),
+                    self.speaker_label(row.corrected_speaker)
+                ));
+                last_corrected = Some(row.corrected_speaker);
+            }
+
+            let words: Vec<String> = tokens
+                .iter()
+                .map(|t| {
+                    let word = display_word(t);
+                    if row.is_relabeled() {
+                        format!("[{}]", word)
+                    } else {
+                        word.to_string()
+                    }
+                })
+                .collect();
+            let text = words.join(" ");
+            left.extend(wrap_column(&text));
+            right.extend(wrap_column(&text));
+
+            // Keep both columns' speaker headers on the same line
+            while left.len() < right.len() {
+                left.insert(0, String::new());
+            }
+            while right.len() < left.len() {
+                right.insert(0, String::new());
+            }
+
+            for (l, r) in left.iter().zip(right.iter()) {
+                let line = format!("{:<width$} | {}", l, r, width = COLUMN_WIDTH);
+                output.push_str(line.trim_end());
+                output.push('\n');
+            }
+
+            if row.is_relabeled() {
+                for token in tokens {
+                    output.push_str(&format!(
+                        "    * {} {:?} {} -> {} ({})\n",
+                        self.options.timestamp(token.start_ms),
+                        token.word,
+                        self.speaker_label(row.original_speaker),
+                        self.speaker_label(row.corrected_speaker),
+                        describe_changes(token)
+                    ));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Format as a standalone HTML page with a two-column table
+    pub fn format_html(&self) -> String {
+        let mut body = String::new();
+        let mut last_original = None;
+        let mut last_corrected = None;
+
+        for row in self.rows() {
+            let tokens = self.tokens(&row);
+            let start = self.options.timestamp(tokens[0].start_ms);
+            let class = if row.is_relabeled() { " class=\"changed\"" } else { "" };
+
+            let mut left = String::new();
+            if last_original != Some(row.original_speaker) {
+                left.push_str(&format!(
+                    "<div class=\"speaker\">[{}] {}</div>",
+                    start,
+                    escape_html(&self.speaker_label(row.original_speaker))
+                ));
+                last_original = Some(row.original_speaker);
+            }
+            let mut right = String::new();
+            if last_corrected != Some(row.corrected_speaker) {
+                right.push_str(&format!(
+                    "<div class=\"speaker\">[{}] {}</div>",
+                    start,
+                    escape_html(&self.speaker_label(row.corrected_speaker))
+                ));
+                last_corrected = Some(row.corrected_speaker);
+            }
+
+            let words: Vec<String> = tokens
+                .iter()
+                .map(|t| {
+                    let word = escape_html(display_word(t));
+                    if row.is_relabeled() {
+                        format!(
+                            "<span class=\"relabel\" title=\"{}\">{}</span>",
+                            escape_html(&describe_changes(t)),
+                            word
+                        )
+                    } else {
+                        word
+                    }
+                })
+                .collect();
+            left.push_str(&words.join(" "));
+            right.push_str(&words.join(" "));
+
+            if row.is_relabeled() {
+                right.push_str("<ul class=\"notes\">");
+                for token in tokens {
+                    right.push_str(&format!(
+                        "<li>{} &ldquo;{}&rdquo;: {} &rarr; {} &mdash; {}</li>",
+                        self.options.timestamp(token.start_ms),
+                        escape_html(&token.word),
+                        escape_html(&self.speaker_label(row.original_speaker)),
+                        escape_html(&self.speaker_label(row.corrected_speaker)),
+                        escape_html(&describe_changes(token))
+                    ));
+                }
+                right.push_str("</ul>");
+            }
+
+            body.push_str(&format!(
+                "<tr{}><td>{}</td><td>{}</td></tr>\n",
+                class, left, right
+            ));
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Transcript diff</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; table-layout: fixed; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 0.4em 0.6em; vertical-align: top; text-align: left; }}
+tr.changed td {{ background: #fff8e1; }}
+.speaker {{ font-weight: bold; color: #444; }}
+.relabel {{ background: #ffcc80; border-radius: 3px; padding: 0 2px; }}
+.notes {{ font-size: 0.85em; color: #555; margin: 0.4em 0 0 1.2em; padding: 0; }}
+</style>
+</head>
+<body>
+<h1>Transcript diff</h1>
+<p>{} of {} tokens relabeled</p>
+<table>
+<tr><th>Original</th><th>Corrected</th></tr>
+{}</table>
+</body>
+</html>
+"#,
+            self.relabeled_count(),
+            self.transcript.tokens.len(),
+            body
+        )
+    }
+
+    /// Write as HTML if the path ends in `.html`/`.htm`, otherwise as text
+    pub fn write_file(&self, path: &Path) -> Result<()> {
+        let is_html = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+        let content = if is_html {
+            self.format_html()
+        } else {
+            self.format_text()
+        };
+
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        write!(file, "{}", content)?;
+        Ok(())
+    }
+}
+
+fn display_word(token: &Token) -> &str {
+    token.punctuated_word.as_deref().unwrap_or(&token.word)
+}
+
+/// Describe every recorded change as `stage: source, reason`
+fn describe_changes(token: &Token) -> String {
+    if token.provenance.is_empty() {
+        return "no provenance recorded".to_string();
+    }
+
+    token
+        .provenance
+        .iter()
+        .map(|c| format!("{}: {}, {}", snake_case(&c.stage), c.source, snake_case(&c.reason)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Serialized (snake_case) name of a unit enum variant
+pub(crate) fn snake_case<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Wrap text to the column width
+fn wrap_column(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + word.len() + 1 > COLUMN_WIDTH {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Escape text for inclusion in HTML
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+    use crate::models::{ChangeStage, ReasonCode};
+
+    fn relabeled_transcript() -> (TokenizedTranscript, Vec<u32>) {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "yeah", "start": 0.4, "end": 0.6, "confidence": 0.95, "speaker": 1},
+                            {"word": "right", "start": 0.7, "end": 1.0, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let original: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
+        transcript.tokens[1].relabel(
+            0,
+            ChangeStage::Heuristics,
+            "collapse_micro_turns",
+            ReasonCode::JitterShortTurn,
+            None,
+        );
+        (transcript, original)
+    }
+
+    #[test]
+    fn test_text_diff_marks_and_annotates_relabels() {
+        let (transcript, original) = relabeled_transcript();
+        let diff = TranscriptDiff::new(&transcript, &original);
+        let text = diff.format_text();
+
+        assert_eq!(diff.relabeled_count(), 1);
+        assert!(text.contains("[yeah]"));
+        assert!(text.contains("Speaker 1 -> Speaker 0 (heuristics: collapse_micro_turns, jitter_short_turn)"));
+    }
+
+    #[test]
+    fn test_html_diff_is_escaped() {
+        let (mut transcript, original) = relabeled_transcript();
+        transcript.tokens[0].word = "<b>".to_string();
+        transcript.tokens[0].punctuated_word = None;
+        let html = TranscriptDiff::new(&transcript, &original).format_html();
+
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(html.contains("class=\"relabel\""));
+    }
+}
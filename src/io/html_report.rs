@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::models::{Token, TokenizedTranscript};
+use crate::stages::ProblemZone;
+
+use super::diff::{escape_html, snake_case};
+use super::output::{format_timestamp, HumanRenderOptions};
+
+/// Number of distinct speaker colors before the palette repeats
+const PALETTE_SIZE: u32 = 8;
+
+/// Self-contained interactive HTML report of a processed transcript
+///
+/// Turns are colored by speaker, every word has a tooltip with its
+/// timestamps and confidences, problem zones are shaded, and a toggle
+/// switches the transcript and timeline between original and corrected labels.
+pub struct HtmlReport<'a> {
+    transcript: &'a TokenizedTranscript,
+    original_speakers: &'a [u32],
+    problem_zones: &'a [ProblemZone],
+    speaker_names: Option<&'a HashMap<u32, String>>,
+    options: HumanRenderOptions,
+}
+
+/// A run of consecutive tokens with the same speaker under one labeling
+struct Run {
+    start: usize,
+    end: usize,
+    speaker: u32,
+}
+
+impl<'a> HtmlReport<'a> {
+    pub fn new(
+        transcript: &'a TokenizedTranscript,
+        original_speakers: &'a [u32],
+        problem_zones: &'a [ProblemZone],
+    ) -> Self {
+        Self {
+            transcript,
+            original_speakers,
+            problem_zones,
+            speaker_names: None,
+            options: HumanRenderOptions::default(),
+        }
+    }
+
+    /// Use participant names for speaker labels
+    pub fn with_speaker_names(mut self, speaker_names: &'a HashMap<u32, String>) -> Self {
+        self.speaker_names = Some(speaker_names);
+        self
+    }
+
+    /// Render timestamps with the offset and recording start of `options`
+    pub fn with_options(mut self, options: HumanRenderOptions) -> Self {
+        self.options = options.spanning(self.transcript.end_ms());
+        self
+    }
+
+    fn speaker_label(&self, speaker: u32) -> String {
+        self.speaker_names
+            .and_then(|names| names.get(&speaker))
+            .cloned()
+            .unwrap_or_else(|| format!("Speaker {}", speaker))
+    }
+
+    /// Group tokens into runs using the given label for each token index
+    fn runs(&self, label: impl Fn(usize) -> u32) -> Vec<Run> {
+        let mut runs: Vec<Run> = Vec::new();
+        for i in 0..self.transcript.tokens.len() {
+            let speaker = label(i);
+            match runs.last_mut() {
+                Some(run) if run.speaker == speaker => run.end = i + 1,
+                _ => runs.push(Run {
+                    start: i,
+                    end: i + 1,
+                    speaker,
+                }),
+            }
+        }
+        runs
+    }
+
+    fn original_runs(&self) -> Vec<Run> {
+        self.runs(|i| {
+            self.original_speakers
+                .get(i)
+                .copied()
+                .unwrap_or(self.transcript.tokens[i].speaker)
+        })
+    }
+
+    fn corrected_runs(&self) -> Vec<Run> {
+        self.runs(|i| self.transcript.tokens[i].speaker)
+    }
+
+    /// Render the complete HTML page
+    pub fn format(&self) -> String {
+        let tokens = &self.transcript.tokens;
+        let relabeled = tokens
+            .iter()
+            .zip(self.original_speakers.iter())
+            .filter(|(t, orig)| t.speaker != **orig)
+            .count();
+
+        // Token index -> problem types covering it
+        let mut zone_types: HashMap<usize, Vec<String>> = HashMap::new();
+        for zone in self.problem_zones {
+            for &i in &zone.token_indices {
+                let name = snake_case(&zone.problem_type);
+                let types = zone_types.entry(i).or_default();
+                if !types.contains(&name) {
+                    types.push(name);
+                }
+            }
+        }
+
+        let mut speakers: Vec<u32> = self.transcript.speakers.clone();
+        for &s in self.original_speakers {
+            if !speakers.contains(&s) {
+                speakers.push(s);
+            }
+        }
+        speakers.sort_unstable();
+
+        let legend: String = speakers
+            .iter()
+            .map(|&s| {
+                format!(
+                    "<span class=\"legend s{}\">{}</span>",
+                    s % PALETTE_SIZE,
+                    escape_html(&self.speaker_label(s))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let original_runs = self.original_runs();
+        let corrected_runs = self.corrected_runs();
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Transcript report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; line-height: 1.5; }}
+.summary span {{ margin-right: 1.5em; }}
+.controls {{ margin: 1em 0; }}
+.timeline {{ position: relative; height: 18px; background: #eee; margin: 0.3em 0; }}
+.timeline div {{ position: absolute; top: 0; bottom: 0; }}
+.timeline .zone {{ background: rgba(220, 0, 0, 0.35); }}
+.strip-label {{ font-size: 0.8em; color: #666; }}
+.turn {{ border-left: 4px solid; padding: 0.2em 0.8em; margin: 0.6em 0; }}
+.who {{ font-weight: bold; font-size: 0.9em; }}
+.tok.zone {{ background: #ffe0e0; }}
+.tok.changed {{ text-decoration: underline wavy #e65100; }}
+.legend {{ padding: 0 0.5em; border-left: 12px solid; }}
+.s0 {{ border-color: #1e88e5; }} .timeline .s0 {{ background: #1e88e5; }}
+.s1 {{ border-color: #43a047; }} .timeline .s1 {{ background: #43a047; }}
+.s2 {{ border-color: #8e24aa; }} .timeline .s2 {{ background: #8e24aa; }}
+.s3 {{ border-color: #fb8c00; }} .timeline .s3 {{ background: #fb8c00; }}
+.s4 {{ border-color: #00acc1; }} .timeline .s4 {{ background: #00acc1; }}
+.s5 {{ border-color: #6d4c41; }} .timeline .s5 {{ background: #6d4c41; }}
+.s6 {{ border-color: #d81b60; }} .timeline .s6 {{ background: #d81b60; }}
+.s7 {{ border-color: #546e7a; }} .timeline .s7 {{ background: #546e7a; }}
+.original {{ display: none; }}
+body.show-original .original {{ display: block; }}
+body.show-original .corrected {{ display: none; }}
+</style>
+</head>
+<body>
+<h1>Transcript report</h1>
+<div class="summary">
+<span>{} tokens</span><span>{} relabeled</span><span>{} problem zones</span><span>{} long</span>
+</div>
+<div class="legend-row">{}</div>
+<div class="controls">
+<label><input type="checkbox" id="toggle-original"> Show original labels</label>
+</div>
+<div class="strip-label">Speaker activity</div>
+<div class="timeline original">{}</div>
+<div class="timeline corrected">{}</div>
+<div class="strip-label">Problem zones</div>
+<div class="timeline">{}</div>
+<div class="original">{}</div>
+<div class="corrected">{}</div>
+<script>
+document.getElementById('toggle-original').addEventListener('change', function () {{
+  document.body.classList.toggle('show-original', this.checked);
+}});
+</script>
+</body>
+</html>
+"#,
+            tokens.len(),
+            relabeled,
+            self.problem_zones.len(),
+            format_timestamp(self.transcript.duration_ms()),
+            legend,
+            self.format_timeline(&original_runs),
+            self.format_timeline(&corrected_runs),
+            self.format_zone_strip(),
+            self.format_turns(&original_runs, &zone_types),
+            self.format_turns(&corrected_runs, &zone_types),
+        )
+    }
+
+    fn format_timeline(&self, runs: &[Run]) -> String {
+        let tokens = &self.transcript.tokens;
+        runs.iter()
+            .map(|run| {
+                let start = tokens[run.start].start_ms;
+                let end = tokens[run.end - 1].end_ms;
+                format!(
+                    "<div class=\"s{}\" style=\"{}\" title=\"{} {}-{}\"></div>",
+                    run.speaker % PALETTE_SIZE,
+                    self.position_style(start, end.saturating_sub(start)),
+                    escape_html(&self.speaker_label(run.speaker)),
+                    self.options.timestamp(start),
+                    self.options.timestamp(end)
+                )
+            })
+            .collect()
+    }
+
+    fn format_zone_strip(&self) -> String {
+        self.problem_zones
+            .iter()
+            .map(|zone| {
+                let length_ms = zone.end_ms.saturating_sub(zone.start_ms).max(1);
+                format!(
+                    "<div class=\"zone\" style=\"{}\" title=\"{} {}-{}\"></div>",
+                    self.position_style(zone.start_ms, length_ms),
+                    snake_case(&zone.problem_type),
+                    self.options.timestamp(zone.start_ms),
+                    self.options.timestamp(zone.end_ms)
+                )
+            })
+            .collect()
+    }
+
+    /// CSS placing a timeline segment by start and length
+    ///
+    /// The timeline spans the transcript from its first word, so positions
+    /// are measured from that word's start.
+    fn position_style(&self, start_ms: u64, length_ms: u64) -> String {
+        let origin_ms = self.transcript.tokens.first().map_or(0, |t| t.start_ms);
+        let duration_ms = self.transcript.duration_ms().max(1);
+        let percent = |ms: u64| ms as f64 / duration_ms as f64 * 100.0;
+        format!(
+            "left:{:.3}%;width:{:.3}%",
+            percent(start_ms.saturating_sub(origin_ms)),
+            percent(length_ms)
+        )
+    }
+
+    fn format_turns(&self, runs: &[Run], zone_types: &HashMap<usize, Vec<String>>) -> String {
+        let mut output = String::new();
+
+        for run in runs {
+            let tokens = &self.transcript.tokens[run.start..run.end];
+            output.push_str(&format!(
+                "<div class=\"turn s{}\"><div class=\"who\">[{}] {}</div>",
+                run.speaker % PALETTE_SIZE,
+                self.options.timestamp(tokens[0].start_ms),
+                escape_html(&self.speaker_label(run.speaker))
+            ));
+
+            let words: Vec<String> = tokens
+                .iter()
+                .enumerate()
+                .map(|(offset, token)| self.format_token(run.start + offset, token, zone_types))
+                .collect();
+            output.push_str(&words.join(" "));
+            output.push_str("</div>\n");
+        }
+
+        output
+    }
+
+    fn format_token(
+        &self,
+        index: usize,
+        token: &Token,
+        zone_types: &HashMap<usize, Vec<String>>,
+    ) -> String {
+        let original = self.original_speakers.get(index).copied().unwrap_or(token.speaker);
+        let mut classes = vec!["tok"];
+        let speaker_conf = match token.speaker_confidence() {
+            Some(conf) if token.is_speaker_conf_estimated() => format!("{:.2} (estimated)", conf),
+            Some(conf) => format!("{:.2}", conf),
+            None => "unknown".to_string(),
+        };
+        let mut title = format!(
+            "{} - {} | speaker conf {} | transcription conf {:.2}",
+            self.options.timestamp(token.start_ms),
+            self.options.timestamp(token.end_ms),
+            speaker_conf,
+            token.transcription_conf
+        );
+
+        if original != token.speaker {
+            classes.push("changed");
+            title.push_str(&format!(
+                " | {} -> {}",
+                self.speaker_label(original),
+                self.speaker_label(token.speaker)
+            ));
+            if let Some(change) = token.provenance.last() {
+                title.push_str(&format!(
+                    " ({}: {}, {})",
+                    snake_case(&change.stage),
+                    change.source,
+                    snake_case(&change.reason)
+                ));
+            }
+        }
+        if let Some(types) = zone_types.get(&index) {
+            classes.push("zone");
+            title.push_str(&format!(" | zone: {}", types.join(", ")));
+        }
+
+        format!(
+            "<span class=\"{}\" title=\"{}\">{}</span>",
+            classes.join(" "),
+            escape_html(&title),
+            escape_html(token.punctuated_word.as_deref().unwrap_or(&token.word))
+        )
+    }
+
+    /// Write to an HTML file
+    pub fn write_file(&self, path: &Path) -> Result<()> {
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        write!(file, "{}", self.format())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+    use crate::models::ProblemType;
+
+    #[test]
+    fn test_report_contains_both_labelings_and_zones() {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "yeah", "start": 0.4, "end": 0.6, "confidence": 0.95, "speaker": 1},
+                            {"word": "right", "start": 0.7, "end": 1.0, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let original: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
+        transcript.tokens[1].speaker = 0;
+        let zones = vec![ProblemZone {
+            start_ms: 400,
+            end_ms: 600,
+            problem_type: ProblemType::ShortTurn,
+            token_indices: vec![1],
+        }];
+
+        let html = HtmlReport::new(&transcript, &original, &zones).format();
+
+        // Original view has three turns, corrected view one
+        assert_eq!(html.matches("<div class=\"turn").count(), 4);
+        assert!(html.contains("class=\"tok changed zone\""));
+        assert!(html.contains("zone: short_turn"));
+        assert!(html.contains("Speaker 1 -&gt; Speaker 0"));
+    }
+
+    #[test]
+    fn test_timeline_measured_from_first_word() {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "late", "start": 10.0, "end": 11.0, "confidence": 0.95, "speaker": 0},
+                            {"word": "start", "start": 11.0, "end": 12.0, "confidence": 0.95, "speaker": 1}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let transcript = parse_deepgram_json(json).unwrap();
+        let original: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
+
+        let html = HtmlReport::new(&transcript, &original, &[]).format();
+
+        assert!(html.contains("style=\"left:0.000%;width:50.000%\""));
+        assert!(html.contains("style=\"left:50.000%;width:50.000%\""));
+    }
+}
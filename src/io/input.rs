@@ -45,18 +45,19 @@ fn tokenize_deepgram_response(response: &DeepgramResponse) -> Result<TokenizedTr
 
         if speaker_changed {
             // Close the current turn
-            if let Some(speaker) = current_speaker {
-                if let Some(last_token) = tokens.last() {
-                    let turn = Turn {
-                        turn_id: format!("turn_{}", current_turn_id),
-                        speaker,
-                        start_ms: current_turn_start_ms,
-                        end_ms: last_token.end_ms,
-                        token_indices: (current_turn_start_index..tokens.len()).collect(),
-                    };
-                    turns.push(turn);
-                    current_turn_id += 1;
-                }
+            if let Some(speaker) = current_speaker
+                && let Some(last_token) = tokens.last()
+            {
+                let turn = Turn {
+                    turn_id: format!("turn_{}", current_turn_id),
+                    speaker,
+                    start_ms: current_turn_start_ms,
+                    end_ms: last_token.end_ms,
+                    token_indices: (current_turn_start_index..tokens.len()).collect(),
+                    pause_before_ms: tokens[current_turn_start_index].pause_before_ms,
+                };
+                turns.push(turn);
+                current_turn_id += 1;
             }
             current_turn_start_index = tokens.len();
             current_turn_start_ms = (word.start * 1000.0) as u64;
@@ -70,7 +71,10 @@ fn tokenize_deepgram_response(response: &DeepgramResponse) -> Result<TokenizedTr
         speakers.insert(word.speaker);
 
         let turn_id = format!("turn_{}", current_turn_id);
-        let token = Token::from_deepgram(word, index, &segment_id, &turn_id);
+        let mut token = Token::from_deepgram(word, index, &segment_id, &turn_id);
+        if let Some(prev) = tokens.last() {
+            token.pause_before_ms = token.start_ms.saturating_sub(prev.end_ms);
+        }
         tokens.push(token);
     }
 
@@ -82,6 +86,7 @@ fn tokenize_deepgram_response(response: &DeepgramResponse) -> Result<TokenizedTr
             start_ms: current_turn_start_ms,
             end_ms: last_token.end_ms,
             token_indices: (current_turn_start_index..tokens.len()).collect(),
+            pause_before_ms: tokens[current_turn_start_index].pause_before_ms,
         };
         turns.push(turn);
     }
@@ -131,6 +136,8 @@ mod tests {
         // Second turn: speaker 1, "how are you"
         assert_eq!(transcript.turns[1].speaker, 1);
         assert_eq!(transcript.turns[1].token_indices, vec![2, 3, 4]);
+        assert_eq!(transcript.turns[1].pause_before_ms, 300);
+        assert_eq!(transcript.tokens[0].pause_before_ms, 0);
     }
 
     #[test]
@@ -1,5 +1,17 @@
+pub mod analysis;
+pub mod analytics;
+pub mod change_set;
+pub mod diff;
+pub mod html_report;
 pub mod input;
 pub mod output;
+pub mod review;
 
+pub use analysis::*;
+pub use analytics::*;
+pub use change_set::*;
+pub use diff::*;
+pub use html_report::*;
 pub use input::*;
 pub use output::*;
+pub use review::*;
@@ -1,11 +1,18 @@
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::Path;
+use std::str::FromStr;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, FixedOffset};
 use serde::Serialize;
 
-use crate::models::{SpeakerIdentification, TokenizedTranscript};
+use crate::models::{
+    LabelChange, SpeakerIdentification, SpeakerMerge, TokenizedTranscript, Turn,
+};
+
+/// Narrowest text column a legal transcript line wraps to after the label
+const MIN_LEGAL_WRAP_WIDTH: usize = 20;
 
 /// Machine-readable output format
 #[derive(Debug, Clone, Serialize)]
@@ -32,7 +39,13 @@ pub struct MachineToken {
     pub speaker: u32,
     pub original_speaker: u32,
     pub was_relabeled: bool,
-    pub speaker_confidence: f64,
+    /// Diarizer speaker confidence, else the Stage 0 estimate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker_confidence: Option<f64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub speaker_confidence_estimated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub provenance: Vec<LabelChange>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -53,6 +66,9 @@ pub struct TranscriptMetadata {
     pub tokens_relabeled: usize,
     pub duration_ms: u64,
     pub windows_processed: usize,
+    /// Proposed speaker merges, applied or not
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub speaker_merges: Vec<SpeakerMerge>,
 }
 
 impl MachineTranscript {
@@ -76,7 +92,9 @@ impl MachineTranscript {
                 speaker: t.speaker,
                 original_speaker: orig,
                 was_relabeled: t.speaker != orig,
-                speaker_confidence: t.speaker_conf,
+                speaker_confidence: t.speaker_confidence(),
+                speaker_confidence_estimated: t.is_speaker_conf_estimated(),
+                provenance: t.provenance.clone(),
             })
             .collect();
 
@@ -111,10 +129,179 @@ impl MachineTranscript {
     }
 }
 
+/// Layout of the human-readable transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HumanFormat {
+    /// `[timestamp] Speaker:` header followed by wrapped text
+    #[default]
+    Text,
+    /// Markdown with a heading per turn
+    Markdown,
+    /// Compact `Name: text`, one line per paragraph
+    Chat,
+    /// Deposition style: numbered lines, fixed-length pages, page breaks
+    Legal,
+}
+
+impl FromStr for HumanFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "chat" => Ok(Self::Chat),
+            "legal" => Ok(Self::Legal),
+            _ => bail!("Unknown human format {:?} (expected text, markdown, chat or legal)", s),
+        }
+    }
+}
+
+/// How timestamps are written in human output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+    /// `MM:SS.mmm`, or `H:MM:SS.mmm` from one hour on
+    #[default]
+    Millis,
+    /// `HH:MM:SS`
+    Seconds,
+}
+
+impl TimestampFormat {
+    /// Format a timestamp in milliseconds
+    pub fn format(&self, ms: u64) -> String {
+        match self {
+            Self::Millis => format_timestamp(ms),
+            Self::Seconds => {
+                let seconds = ms / 1000;
+                format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+            }
+        }
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "millis" | "mm:ss.mmm" => Ok(Self::Millis),
+            "seconds" | "hh:mm:ss" => Ok(Self::Seconds),
+            _ => bail!("Unknown timestamp format {:?} (expected millis or seconds)", s),
+        }
+    }
+}
+
+/// How speakers without an identified name are labeled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnidentifiedLabel {
+    /// `Speaker N`
+    #[default]
+    Numbered,
+    /// `Unknown`
+    Unknown,
+}
+
+impl FromStr for UnidentifiedLabel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "numbered" | "speaker" => Ok(Self::Numbered),
+            "unknown" => Ok(Self::Unknown),
+            _ => bail!("Unknown speaker label {:?} (expected numbered or unknown)", s),
+        }
+    }
+}
+
+/// Options for rendering the human-readable transcript
+#[derive(Debug, Clone)]
+pub struct HumanRenderOptions {
+    /// Output layout
+    pub format: HumanFormat,
+    /// Timestamp style
+    pub timestamp_format: TimestampFormat,
+    /// Split turns longer than this many words into paragraphs (at the next
+    /// sentence end, or at twice the limit)
+    pub max_paragraph_words: Option<usize>,
+    /// Label for speakers without an identified name
+    pub unidentified_label: UnidentifiedLabel,
+    /// Line width for wrapped text
+    pub wrap_width: usize,
+    /// Numbered lines per page in the legal format
+    pub legal_lines_per_page: usize,
+    /// Recording start; when set, timestamps are rendered as wall-clock times
+    /// in this datetime's time zone
+    pub recording_start: Option<DateTime<FixedOffset>>,
+    /// Added to every timestamp, for transcripts cut from a longer recording
+    pub offset_ms: u64,
+    /// Prefix wall-clock timestamps with the date; set by `spanning` when the
+    /// recording runs past the day it started on
+    pub include_date: bool,
+}
+
+impl HumanRenderOptions {
+    /// Render a transcript timestamp, applying the offset and recording start
+    pub fn timestamp(&self, ms: u64) -> String {
+        let ms = ms + self.offset_ms;
+        match self.recording_start {
+            Some(start) => {
+                let time = start + Duration::milliseconds(ms as i64);
+                let format = match (self.timestamp_format, self.include_date) {
+                    (TimestampFormat::Millis, false) => "%H:%M:%S%.3f",
+                    (TimestampFormat::Seconds, false) => "%H:%M:%S",
+                    (TimestampFormat::Millis, true) => "%Y-%m-%d %H:%M:%S%.3f",
+                    (TimestampFormat::Seconds, true) => "%Y-%m-%d %H:%M:%S",
+                };
+                time.format(format).to_string()
+            }
+            None => self.timestamp_format.format(ms),
+        }
+    }
+
+    /// Copy of these options for a recording whose last word ends at `end_ms`
+    ///
+    /// Wall-clock timestamps include the date when the recording runs past
+    /// the day it started on.
+    pub fn spanning(&self, end_ms: u64) -> Self {
+        let include_date = self.recording_start.is_some_and(|start| {
+            let end = start + Duration::milliseconds((end_ms + self.offset_ms) as i64);
+            end.date_naive() != start.date_naive()
+        });
+        Self {
+            include_date,
+            ..self.clone()
+        }
+    }
+
+    /// Line stating the recording start date and time zone, if set
+    fn recording_start_line(&self) -> Option<String> {
+        self.recording_start
+            .map(|start| format!("Recording started {}", start.format("%Y-%m-%d %H:%M:%S %:z")))
+    }
+}
+
+impl Default for HumanRenderOptions {
+    fn default() -> Self {
+        Self {
+            format: HumanFormat::Text,
+            timestamp_format: TimestampFormat::Millis,
+            max_paragraph_words: None,
+            unidentified_label: UnidentifiedLabel::Numbered,
+            wrap_width: 80,
+            legal_lines_per_page: 25,
+            recording_start: None,
+            offset_ms: 0,
+            include_date: false,
+        }
+    }
+}
+
 /// Human-readable transcript format
 pub struct HumanTranscript<'a> {
     transcript: &'a TokenizedTranscript,
     speaker_names: Option<&'a HashMap<u32, String>>,
+    options: HumanRenderOptions,
 }
 
 impl<'a> HumanTranscript<'a> {
@@ -122,6 +309,7 @@ impl<'a> HumanTranscript<'a> {
         Self {
             transcript,
             speaker_names: None,
+            options: HumanRenderOptions::default(),
         }
     }
 
@@ -133,41 +321,162 @@ impl<'a> HumanTranscript<'a> {
         Self {
             transcript,
             speaker_names: Some(speaker_names),
+            options: HumanRenderOptions::default(),
+        }
+    }
+
+    /// Set rendering options
+    pub fn with_options(mut self, options: HumanRenderOptions) -> Self {
+        self.options = options.spanning(self.transcript.end_ms());
+        self
+    }
+
+    fn speaker_label(&self, speaker: u32) -> String {
+        match self.speaker_names.and_then(|names| names.get(&speaker)) {
+            Some(name) => name.clone(),
+            None => match self.options.unidentified_label {
+                UnidentifiedLabel::Numbered => format!("Speaker {}", speaker),
+                UnidentifiedLabel::Unknown => "Unknown".to_string(),
+            },
+        }
+    }
+
+    /// Paragraphs of a turn, preferring punctuated words when available
+    fn paragraphs(&self, turn: &Turn) -> Vec<String> {
+        let words: Vec<&str> = turn
+            .token_indices
+            .iter()
+            .filter_map(|&i| self.transcript.tokens.get(i))
+            .map(|t| t.punctuated_word.as_deref().unwrap_or(&t.word))
+            .collect();
+
+        let Some(max_words) = self.options.max_paragraph_words.filter(|&n| n > 0) else {
+            return vec![words.join(" ")];
+        };
+
+        let mut paragraphs = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        for word in words {
+            current.push(word);
+            let sentence_end = word.ends_with(['.', '?', '!']);
+            if (current.len() >= max_words && sentence_end) || current.len() >= max_words * 2 {
+                paragraphs.push(current.join(" "));
+                current.clear();
+            }
         }
+        if !current.is_empty() {
+            paragraphs.push(current.join(" "));
+        }
+
+        paragraphs
     }
 
-    /// Format the transcript as human-readable text
+    /// Format the transcript in the configured layout
     pub fn format(&self) -> String {
+        match self.options.format {
+            HumanFormat::Text => self.format_text(),
+            HumanFormat::Markdown => self.format_markdown(),
+            HumanFormat::Chat => self.format_chat(),
+            HumanFormat::Legal => self.format_legal(),
+        }
+    }
+
+    fn format_text(&self) -> String {
         let mut output = String::new();
+        if let Some(line) = self.options.recording_start_line() {
+            output.push_str(&format!("{}\n\n", line));
+        }
 
         for turn in &self.transcript.turns {
             // Format speaker header with timestamp
-            let start_time = format_timestamp(turn.start_ms);
-            let speaker_label = self
-                .speaker_names
-                .and_then(|names| names.get(&turn.speaker))
-                .map(|name| name.clone())
-                .unwrap_or_else(|| format!("Speaker {}", turn.speaker));
-            output.push_str(&format!("[{}] {}:\n", start_time, speaker_label));
-
-            // Collect words for this turn, preferring punctuated words when available
-            let words: Vec<&str> = turn
-                .token_indices
+            let start_time = self.options.timestamp(turn.start_ms);
+            output.push_str(&format!("[{}] {}:\n", start_time, self.speaker_label(turn.speaker)));
+
+            // Wrap each paragraph at the configured width
+            let paragraphs: Vec<String> = self
+                .paragraphs(turn)
                 .iter()
-                .filter_map(|&i| self.transcript.tokens.get(i))
-                .map(|t| t.punctuated_word.as_deref().unwrap_or(&t.word))
+                .map(|p| wrap_text(p, self.options.wrap_width))
                 .collect();
-
-            // Join words with spaces, wrapping at ~80 characters
-            let text = words.join(" ");
-            let wrapped = wrap_text(&text, 80);
-            output.push_str(&wrapped);
+            output.push_str(&paragraphs.join("\n\n"));
             output.push_str("\n\n");
         }
 
         output
     }
 
+    fn format_markdown(&self) -> String {
+        let mut output = String::from("# Transcript\n\n");
+        if let Some(line) = self.options.recording_start_line() {
+            output.push_str(&format!("_{}_\n\n", line));
+        }
+
+        for turn in &self.transcript.turns {
+            output.push_str(&format!(
+                "### {} ({})\n\n",
+                self.speaker_label(turn.speaker),
+                self.options.timestamp(turn.start_ms)
+            ));
+            for paragraph in self.paragraphs(turn) {
+                output.push_str(&wrap_text(&paragraph, self.options.wrap_width));
+                output.push_str("\n\n");
+            }
+        }
+
+        output
+    }
+
+    fn format_chat(&self) -> String {
+        let mut output = String::new();
+
+        for turn in &self.transcript.turns {
+            let label = self.speaker_label(turn.speaker);
+            for paragraph in self.paragraphs(turn) {
+                output.push_str(&format!("{}: {}\n", label, paragraph));
+            }
+        }
+
+        output
+    }
+
+    fn format_legal(&self) -> String {
+        // Leave room for the line number column
+        let text_width = self.options.wrap_width.saturating_sub(6).max(40);
+        let mut lines: Vec<String> = Vec::new();
+
+        for turn in &self.transcript.turns {
+            let label = self.speaker_label(turn.speaker).to_uppercase();
+            for (i, paragraph) in self.paragraphs(turn).iter().enumerate() {
+                // Wrap first: wrap_text collapses the indent and label spacing
+                let prefix = if i == 0 { format!("{}:  ", label) } else { String::new() };
+                // Long speaker labels still leave a usable wrap width
+                let width = text_width.saturating_sub(prefix.len() + 5).max(MIN_LEGAL_WRAP_WIDTH);
+                let wrapped = wrap_text(paragraph, width);
+                for (j, line) in wrapped.lines().enumerate() {
+                    let lead = if j == 0 { prefix.as_str() } else { "" };
+                    lines.push(format!("     {}{}", lead, line));
+                }
+            }
+        }
+
+        let lines_per_page = self.options.legal_lines_per_page.max(1);
+        let pages: Vec<String> = lines
+            .chunks(lines_per_page)
+            .enumerate()
+            .map(|(page, page_lines)| {
+                let header = format!("Page {}", page + 1);
+                let mut out = format!("{:>width$}\n\n", header, width = text_width + 6);
+                for (n, line) in page_lines.iter().enumerate() {
+                    out.push_str(&format!("{:>2}    {}\n", n + 1, line));
+                }
+                out
+            })
+            .collect();
+
+        // Form feed between pages
+        pages.join("\x0c")
+    }
+
     /// Write to a text file
     pub fn write_file(&self, path: &Path) -> Result<()> {
         let mut file = std::fs::File::create(path)
@@ -177,13 +486,18 @@ impl<'a> HumanTranscript<'a> {
     }
 }
 
-/// Format milliseconds as MM:SS.mmm
-fn format_timestamp(ms: u64) -> String {
+/// Format milliseconds as MM:SS.mmm, or H:MM:SS.mmm from one hour on
+pub(crate) fn format_timestamp(ms: u64) -> String {
     let seconds = ms / 1000;
     let millis = ms % 1000;
-    let minutes = seconds / 60;
+    let hours = seconds / 3600;
+    let minutes = seconds / 60 % 60;
     let secs = seconds % 60;
-    format!("{:02}:{:02}.{:03}", minutes, secs, millis)
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+    } else {
+        format!("{:02}:{:02}.{:03}", minutes, secs, millis)
+    }
 }
 
 /// Wrap text at approximately the given width
@@ -216,7 +530,97 @@ mod tests {
         assert_eq!(format_timestamp(0), "00:00.000");
         assert_eq!(format_timestamp(1500), "00:01.500");
         assert_eq!(format_timestamp(65_000), "01:05.000");
-        assert_eq!(format_timestamp(3_661_500), "61:01.500");
+        assert_eq!(format_timestamp(3_661_500), "1:01:01.500");
+    }
+
+    #[test]
+    fn test_wall_clock_timestamps() {
+        let options = HumanRenderOptions {
+            recording_start: Some("2024-03-01T09:30:00-05:00".parse().unwrap()),
+            offset_ms: 60_000,
+            ..Default::default()
+        };
+        assert_eq!(options.timestamp(1500), "09:31:01.500");
+        assert_eq!(
+            options.recording_start_line().unwrap(),
+            "Recording started 2024-03-01 09:30:00 -05:00"
+        );
+
+        let relative = HumanRenderOptions {
+            timestamp_format: TimestampFormat::Seconds,
+            offset_ms: 3_600_000,
+            ..Default::default()
+        };
+        assert_eq!(relative.timestamp(1500), "01:00:01");
+
+        // A recording running past midnight carries the date everywhere
+        let overnight = HumanRenderOptions {
+            recording_start: Some("2024-03-01T23:30:00+00:00".parse().unwrap()),
+            ..Default::default()
+        }
+        .spanning(3_600_000);
+        assert_eq!(overnight.timestamp(1500), "2024-03-01 23:30:01.500");
+        assert_eq!(overnight.timestamp(3_600_000), "2024-03-02 00:30:00.000");
+    }
+
+    #[test]
+    fn test_human_formats() {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "hello", "start": 3700.0, "end": 3700.5, "confidence": 0.95, "speaker": 0, "punctuated_word": "Hello."},
+                            {"word": "again", "start": 3700.6, "end": 3701.0, "confidence": 0.95, "speaker": 0, "punctuated_word": "Again."},
+                            {"word": "hi", "start": 3701.5, "end": 3702.0, "confidence": 0.95, "speaker": 1, "punctuated_word": "Hi."}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let transcript = crate::io::parse_deepgram_json(json).unwrap();
+        let names = HashMap::from([(0, "Alice".to_string())]);
+        let render = |options: HumanRenderOptions| {
+            HumanTranscript::with_speaker_names(&transcript, &names)
+                .with_options(options)
+                .format()
+        };
+
+        let chat = render(HumanRenderOptions {
+            format: HumanFormat::Chat,
+            max_paragraph_words: Some(1),
+            unidentified_label: UnidentifiedLabel::Unknown,
+            ..Default::default()
+        });
+        assert_eq!(chat, "Alice: Hello.\nAlice: Again.\nUnknown: Hi.\n");
+
+        let markdown = render(HumanRenderOptions {
+            format: HumanFormat::Markdown,
+            timestamp_format: TimestampFormat::Seconds,
+            ..Default::default()
+        });
+        assert!(markdown.contains("### Alice (01:01:40)\n\nHello. Again."));
+        assert!(markdown.contains("### Speaker 1 (01:01:41)"));
+
+        let legal = render(HumanRenderOptions {
+            format: HumanFormat::Legal,
+            legal_lines_per_page: 1,
+            ..Default::default()
+        });
+        assert_eq!(legal.matches('\x0c').count(), 1);
+        assert!(legal.contains(" 1         ALICE:  Hello. Again."));
+
+        // A label longer than the text column must not break wrapping
+        let long_name = "Alice Wonderland-Smythe, Counsel for the Defence".to_string();
+        let long_name = HashMap::from([(0, long_name)]);
+        let legal = HumanTranscript::with_speaker_names(&transcript, &long_name)
+            .with_options(HumanRenderOptions {
+                format: HumanFormat::Legal,
+                wrap_width: 40,
+                ..Default::default()
+            })
+            .format();
+        assert!(legal.contains("ALICE WONDERLAND-SMYTHE, COUNSEL FOR THE DEFENCE:  Hello. Again."));
     }
 
     #[test]
@@ -0,0 +1,501 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::heuristics::micro_turns::rebuild_turns;
+use crate::models::{ChangeStage, ReasonCode, TokenizedTranscript, Window, WindowPatch};
+
+use super::change_set::find_token_mut;
+
+/// Column headers of the CSV review export
+const CSV_HEADER: &str =
+    "item_id,kind,original_index,word,start_ms,end_ms,speaker,context,detail,decision";
+
+/// Why an item needs human review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewKind {
+    /// The LLM listed the token in `notes.uncertain_tokens`
+    UncertainToken,
+    /// Windows proposed different labels for the token
+    Conflict,
+    /// The window's patch was rejected, so its problem zones were not corrected.
+    /// Covers many tokens, so it is informational and cannot take a decision.
+    RejectedWindow,
+}
+
+/// A region a human should check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewItem {
+    /// Stable item identifier within the export
+    pub item_id: String,
+    /// Why the item needs review
+    pub kind: ReviewKind,
+    /// Original index of the first token in the region
+    pub original_index: usize,
+    /// Word of the first token in the region
+    pub word: String,
+    /// Start of the region in milliseconds
+    pub start_ms: u64,
+    /// End of the region in milliseconds
+    pub end_ms: u64,
+    /// Current speaker of the token (absent for window regions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<u32>,
+    /// Surrounding text with speaker labels, target tokens marked `>>word<<`
+    pub context: String,
+    /// Windows involved or the rejection error
+    pub detail: String,
+}
+
+/// Items exported for human review
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewExport {
+    /// Items in the order they were added
+    pub items: Vec<ReviewItem>,
+    /// Tokens of context on each side of a token item
+    #[serde(skip)]
+    context_tokens: usize,
+}
+
+impl ReviewExport {
+    /// Create an empty export with the given context size
+    pub fn new(context_tokens: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            context_tokens,
+        }
+    }
+
+    /// Add every token the LLM reported as uncertain, once per token
+    pub fn add_uncertain_tokens(
+        &mut self,
+        transcript: &TokenizedTranscript,
+        patches: &[WindowPatch],
+    ) {
+        let mut windows_by_token: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+        for patch in patches {
+            for token_id in &patch.notes.uncertain_tokens {
+                if let Some(index) = transcript
+                    .tokens
+                    .iter()
+                    .position(|t| t.token_id == *token_id)
+                {
+                    windows_by_token
+                        .entry(index)
+                        .or_default()
+                        .push(patch.window_id.as_str());
+                }
+            }
+        }
+
+        for (index, window_ids) in windows_by_token {
+            let detail = format!("Uncertain in {}", window_ids.join(", "));
+            self.push_token_item(transcript, index, ReviewKind::UncertainToken, detail);
+        }
+    }
+
+    /// Add every token whose candidate labels conflicted in Stage 2
+    pub fn add_conflicts(
+        &mut self,
+        transcript: &TokenizedTranscript,
+        conflict_token_ids: &[String],
+    ) {
+        let mut indices: Vec<usize> = conflict_token_ids
+            .iter()
+            .filter_map(|id| transcript.tokens.iter().position(|t| t.token_id == *id))
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        for index in indices {
+            let detail = "Windows proposed conflicting labels".to_string();
+            self.push_token_item(transcript, index, ReviewKind::Conflict, detail);
+        }
+    }
+
+    /// Add a window whose patch was rejected
+    pub fn add_rejected_window(
+        &mut self,
+        transcript: &TokenizedTranscript,
+        window: &Window,
+        error: &str,
+    ) {
+        let Some(first) = window
+            .token_indices
+            .first()
+            .and_then(|&i| transcript.tokens.get(i))
+        else {
+            return;
+        };
+
+        let item = ReviewItem {
+            item_id: format!("r_{}", self.items.len()),
+            kind: ReviewKind::RejectedWindow,
+            original_index: first.original_index,
+            word: first.word.clone(),
+            start_ms: window.start_ms,
+            end_ms: window.end_ms,
+            speaker: None,
+            context: format_context(transcript, &window.token_indices, &[]),
+            detail: format!("{}: {}", window.window_id, error),
+        };
+        self.items.push(item);
+    }
+
+    fn push_token_item(
+        &mut self,
+        transcript: &TokenizedTranscript,
+        index: usize,
+        kind: ReviewKind,
+        detail: String,
+    ) {
+        let token = &transcript.tokens[index];
+        let from = index.saturating_sub(self.context_tokens);
+        let to = (index + self.context_tokens + 1).min(transcript.tokens.len());
+        let context_indices: Vec<usize> = (from..to).collect();
+
+        let item = ReviewItem {
+            item_id: format!("r_{}", self.items.len()),
+            kind,
+            original_index: token.original_index,
+            word: token.word.clone(),
+            start_ms: token.start_ms,
+            end_ms: token.end_ms,
+            speaker: Some(token.speaker),
+            context: format_context(transcript, &context_indices, &[index]),
+            detail,
+        };
+        self.items.push(item);
+    }
+
+    /// Write to a JSON file
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        serde_json::to_writer_pretty(file, self).context("Failed to write JSON")?;
+        Ok(())
+    }
+
+    /// Write to a CSV file with an empty `decision` column for reviewers
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create file: {:?}", path))?;
+        writeln!(file, "{}", CSV_HEADER)?;
+
+        for item in &self.items {
+            let kind = serde_json::to_value(item.kind)?
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let fields = [
+                item.item_id.clone(),
+                kind,
+                item.original_index.to_string(),
+                item.word.clone(),
+                item.start_ms.to_string(),
+                item.end_ms.to_string(),
+                item.speaker.map(|s| s.to_string()).unwrap_or_default(),
+                item.context.clone(),
+                item.detail.clone(),
+                String::new(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            writeln!(file, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write as CSV if the path ends in `.csv`, otherwise as JSON
+    pub fn write_file(&self, path: &Path) -> Result<()> {
+        if is_csv(path) {
+            self.write_csv(path)
+        } else {
+            self.write_json(path)
+        }
+    }
+}
+
+/// A reviewer's decision on a token's speaker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewDecision {
+    /// Original index of the token
+    pub original_index: usize,
+    /// Word of the token, used to verify the match
+    pub word: String,
+    /// Start timestamp in milliseconds, used to verify the match
+    pub start_ms: u64,
+    /// Speaker chosen by the reviewer
+    pub speaker: u32,
+}
+
+/// Read reviewer decisions from a JSON array or a filled-in CSV export
+///
+/// CSV rows with an empty `decision` column are ignored; a decision on a
+/// rejected-window row is an error, since the row covers many tokens.
+pub fn read_review_decisions(path: &Path) -> Result<Vec<ReviewDecision>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {:?}", path))?;
+
+    if is_csv(path) {
+        parse_decisions_csv(&content)
+    } else {
+        serde_json::from_str(&content).context("Failed to parse review decisions JSON")
+    }
+}
+
+/// Apply reviewer decisions as locked labels
+///
+/// Every decided token is locked so that no later heuristic or LLM stage can
+/// change it. Returns the number of tokens whose speaker changed.
+pub fn apply_review_decisions(
+    transcript: &mut TokenizedTranscript,
+    decisions: &[ReviewDecision],
+) -> Result<usize> {
+    let mut changed = 0;
+
+    for decision in decisions {
+        let token = find_token_mut(
+            transcript,
+            decision.original_index,
+            &decision.word,
+            decision.start_ms,
+        )?;
+
+        if token.speaker != decision.speaker {
+            token.relabel(
+                decision.speaker,
+                ChangeStage::Review,
+                "review",
+                ReasonCode::HumanReview,
+                None,
+            );
+            changed += 1;
+        }
+        token.locked = true;
+    }
+
+    if changed > 0 {
+        rebuild_turns(transcript);
+    }
+
+    Ok(changed)
+}
+
+fn parse_decisions_csv(content: &str) -> Result<Vec<ReviewDecision>> {
+    let mut records = parse_csv(content).into_iter();
+    let header = records.next().unwrap_or_default();
+    let column = |name: &str| -> Result<usize> {
+        header
+            .iter()
+            .position(|h| h == name)
+            .with_context(|| format!("Review CSV is missing the {:?} column", name))
+    };
+    let index_col = column("original_index")?;
+    let word_col = column("word")?;
+    let start_col = column("start_ms")?;
+    let decision_col = column("decision")?;
+    let kind_col = column("kind").ok();
+
+    let mut decisions = Vec::new();
+    for (row_no, fields) in records.enumerate() {
+        let field = |i: usize| fields.get(i).map(|f| f.trim()).unwrap_or_default();
+
+        if field(decision_col).is_empty() {
+            continue;
+        }
+        let parse_err = || format!("Invalid review CSV row {}", row_no + 2);
+        if kind_col.is_some_and(|col| field(col) == "rejected_window") {
+            bail!(
+                "Review CSV row {} is a rejected window and cannot take a decision; \
+                 decide its tokens individually in a JSON decisions file",
+                row_no + 2
+            );
+        }
+        decisions.push(ReviewDecision {
+            original_index: field(index_col).parse().with_context(parse_err)?,
+            word: field(word_col).to_string(),
+            start_ms: field(start_col).parse().with_context(parse_err)?,
+            speaker: field(decision_col).parse().with_context(parse_err)?,
+        });
+    }
+
+    Ok(decisions)
+}
+
+/// Render tokens as `[S0] word word [S1] word`, marking `targets`
+fn format_context(
+    transcript: &TokenizedTranscript,
+    indices: &[usize],
+    targets: &[usize],
+) -> String {
+    let mut parts = Vec::new();
+    let mut last_speaker = None;
+
+    for &i in indices {
+        let Some(token) = transcript.tokens.get(i) else {
+            continue;
+        };
+        if last_speaker != Some(token.speaker) {
+            parts.push(format!("[S{}]", token.speaker));
+            last_speaker = Some(token.speaker);
+        }
+        let word = token.punctuated_word.as_deref().unwrap_or(&token.word);
+        if targets.contains(&i) {
+            parts.push(format!(">>{}<<", word));
+        } else {
+            parts.push(word.to_string());
+        }
+    }
+
+    parts.join(" ")
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Split CSV content into records of fields, honouring quoted fields
+///
+/// Quoted fields may contain delimiters, doubled quotes and line breaks.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut current));
+                records.push(std::mem::take(&mut fields));
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() || !fields.is_empty() {
+        fields.push(current);
+        records.push(fields);
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+    use crate::models::PatchNotes;
+
+    fn transcript() -> TokenizedTranscript {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "yeah", "start": 0.4, "end": 0.6, "confidence": 0.95, "speaker": 1},
+                            {"word": "right", "start": 0.7, "end": 1.0, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        parse_deepgram_json(json).unwrap()
+    }
+
+    #[test]
+    fn test_export_uncertain_tokens_as_csv_round_trip() {
+        let transcript = transcript();
+        let patch = WindowPatch {
+            window_id: "w_0".to_string(),
+            token_relabels: vec![],
+            turn_edits: vec![],
+            violations: vec![],
+            notes: PatchNotes {
+                uncertain_tokens: vec![transcript.tokens[1].token_id.clone()],
+                summary: String::new(),
+            },
+            quality: Default::default(),
+        };
+
+        let mut export = ReviewExport::new(1);
+        export.add_uncertain_tokens(&transcript, &[patch]);
+        assert_eq!(export.items.len(), 1);
+        assert_eq!(export.items[0].context, "[S0] so [S1] >>yeah<< [S0] right");
+
+        // A reviewer fills in the decision column
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("review.csv");
+        export.write_csv(&path).unwrap();
+        let filled = std::fs::read_to_string(&path).unwrap().trim_end().to_string() + "0\n";
+        std::fs::write(&path, filled).unwrap();
+
+        let decisions = read_review_decisions(&path).unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].speaker, 0);
+        assert_eq!(decisions[0].word, "yeah");
+    }
+
+    #[test]
+    fn test_rejected_window_rows_are_not_decidable() {
+        let csv = format!("{}\nr_0,rejected_window,0,so,0,1000,,[S0] so,w_0: bad,1\n", CSV_HEADER);
+
+        assert!(parse_decisions_csv(&csv).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_quoted_newlines() {
+        let csv = format!(
+            "{}\r\nr_0,conflict,1,yeah,400,600,1,\"[S0] so\n>>yeah<<, \"\"ok\"\"\",w_0,0\r\n",
+            CSV_HEADER
+        );
+
+        let decisions = parse_decisions_csv(&csv).unwrap();
+
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].original_index, 1);
+        assert_eq!(decisions[0].speaker, 0);
+    }
+
+    #[test]
+    fn test_apply_decisions_locks_tokens() {
+        let mut transcript = transcript();
+        let decisions = vec![ReviewDecision {
+            original_index: 1,
+            word: "yeah".to_string(),
+            start_ms: 400,
+            speaker: 0,
+        }];
+
+        let changed = apply_review_decisions(&mut transcript, &decisions).unwrap();
+
+        assert_eq!(changed, 1);
+        assert!(transcript.tokens[1].locked);
+        assert_eq!(transcript.tokens[1].speaker, 0);
+        assert_eq!(transcript.tokens[1].provenance[0].stage, ChangeStage::Review);
+        assert_eq!(transcript.turns.len(), 1);
+    }
+}
@@ -5,13 +5,20 @@ pub mod models;
 pub mod stages;
 
 pub use heuristics::{apply_heuristics, HeuristicsConfig};
-pub use io::{parse_deepgram_file, parse_deepgram_json, HumanTranscript, MachineTranscript, TranscriptMetadata};
-pub use llm::{AnthropicClient, AnthropicConfig};
+pub use io::{
+    apply_review_decisions, parse_deepgram_file, parse_deepgram_json, read_review_decisions,
+    AnalysisFormat, ChangeSet, HtmlReport, HumanFormat, HumanRenderOptions, HumanTranscript,
+    MachineTranscript, ReviewExport, TimestampFormat, TranscriptAnalysis, TranscriptAnalytics,
+    TranscriptDiff, TranscriptMetadata, UnidentifiedLabel,
+};
+pub use llm::{AnthropicClient, AnthropicConfig, CostEstimate, ModelPricing};
 pub use models::{
     DeepgramResponse, Participant, ProblemZoneConfig, SpeakerIdConfig, SpeakerIdResult,
-    SpeakerIdentification, Token, TokenizedTranscript, WindowConfig, WindowPatch,
+    SpeakerIdentification, SpeakerMerge, SpeakerMergeConfig, SpeakerMergeResult, Token,
+    TokenizedTranscript, WindowConfig, WindowPatch, WindowStrategy,
 };
 pub use stages::{
-    execute_speaker_id, execute_stage1, execute_stage2, execute_stage3, normalize,
-    parse_participants_file, parse_participants_string, Stage1Config, Stage2Config, Stage3Config,
+    estimate_stage1, execute_speaker_id, execute_speaker_merge, execute_stage1, execute_stage2,
+    execute_stage3, normalize, parse_participants_file, parse_participants_string,
+    ReconciliationStrategy, Stage1Budget, Stage1Config, Stage2Config, Stage3Config, Stage3Outputs,
 };
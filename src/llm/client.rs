@@ -7,8 +7,13 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
+use crate::llm::prompts::get_patch_tool_schema;
 use crate::llm::speaker_id_prompt::get_speaker_id_tool_schema;
-use crate::models::{SpeakerIdentification, WindowPatch};
+use crate::llm::speaker_merge_prompt::get_speaker_merge_tool_schema;
+use crate::models::{SpeakerIdentification, SpeakerMergeVerdict, WindowPatch};
+
+/// Model used when none is configured
+pub const DEFAULT_MODEL: &str = "claude-haiku-4-5-20251001";
 
 /// Configuration for the Anthropic API client
 #[derive(Debug, Clone)]
@@ -31,7 +36,7 @@ impl AnthropicConfig {
 
         Ok(Self {
             api_key,
-            model: "claude-haiku-4-5-20251001".to_string(),
+            model: DEFAULT_MODEL.to_string(),
             temperature: 0.1,
             max_tokens: 4096,
         })
@@ -71,10 +76,10 @@ pub struct AnthropicClient {
 impl AnthropicClient {
     pub fn new(config: AnthropicConfig, log_dir: Option<PathBuf>) -> Self {
         // Create log directory if specified and doesn't exist
-        if let Some(ref dir) = log_dir {
-            if let Err(e) = std::fs::create_dir_all(dir) {
-                warn!("Failed to create log directory {:?}: {}", dir, e);
-            }
+        if let Some(ref dir) = log_dir
+            && let Err(e) = std::fs::create_dir_all(dir)
+        {
+            warn!("Failed to create log directory {:?}: {}", dir, e);
         }
 
         Self {
@@ -85,6 +90,11 @@ impl AnthropicClient {
         }
     }
 
+    /// Model used for requests
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
     /// Write a log entry to a file
     fn write_log_entry(&self, method: &str, entry: &LogEntry) {
         let Some(ref dir) = self.log_dir else {
@@ -93,7 +103,7 @@ impl AnthropicClient {
 
         let seq = self.log_sequence.fetch_add(1, Ordering::SeqCst);
         // Use timestamp with underscores instead of colons for filename compatibility
-        let timestamp = entry.timestamp.replace(':', "-").replace('.', "-");
+        let timestamp = entry.timestamp.replace([':', '.'], "-");
         let filename = format!("{}_{:03}_{}.json", timestamp, seq, method);
         let path = dir.join(&filename);
 
@@ -195,63 +205,7 @@ impl AnthropicClient {
         let tool = Tool {
             name: "submit_patch".to_string(),
             description: "Submit the window patch with token relabels and turn edits".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "window_id": {
-                        "type": "string",
-                        "description": "ID of the window being patched"
-                    },
-                    "token_relabels": {
-                        "type": "array",
-                        "items": {
-                            "type": "object",
-                            "properties": {
-                                "token_id": {"type": "string"},
-                                "new_speaker": {"type": "integer"},
-                                "reason": {
-                                    "type": "string",
-                                    "enum": ["jitter_short_turn", "overlap_boundary", "lexical_continuity", "dialogue_pairing", "backchannel_attribution", "do_not_change"]
-                                }
-                            },
-                            "required": ["token_id", "new_speaker", "reason"]
-                        }
-                    },
-                    "turn_edits": {
-                        "type": "array",
-                        "items": {
-                            "type": "object",
-                            "properties": {
-                                "type": {"type": "string", "enum": ["merge_turns", "split_turn"]},
-                                "turn_id": {"type": "string"},
-                                "to_turn_id": {"type": "string"},
-                                "split_at_token_id": {"type": "string"},
-                                "reason": {
-                                    "type": "string",
-                                    "enum": ["jitter_short_turn", "overlap_boundary", "lexical_continuity", "dialogue_pairing", "backchannel_attribution", "do_not_change"]
-                                }
-                            },
-                            "required": ["type", "turn_id", "reason"]
-                        }
-                    },
-                    "violations": {
-                        "type": "array",
-                        "items": {"type": "string"},
-                        "description": "List any rules you may have violated"
-                    },
-                    "notes": {
-                        "type": "object",
-                        "properties": {
-                            "uncertain_tokens": {
-                                "type": "array",
-                                "items": {"type": "string"}
-                            },
-                            "summary": {"type": "string"}
-                        }
-                    }
-                },
-                "required": ["window_id", "token_relabels", "turn_edits", "violations", "notes"]
-            }),
+            input_schema: get_patch_tool_schema(),
         };
 
         let start = Instant::now();
@@ -326,13 +280,13 @@ impl AnthropicClient {
 
         // Find the tool_use content block
         for content in &response.content {
-            if content.content_type == "tool_use" && content.name.as_deref() == Some("submit_patch")
+            if content.content_type == "tool_use"
+                && content.name.as_deref() == Some("submit_patch")
+                && let Some(input) = &content.input
             {
-                if let Some(input) = &content.input {
-                    let patch: WindowPatch = serde_json::from_value(input.clone())
-                        .context("Failed to parse tool input as WindowPatch")?;
-                    return Ok((patch, response.usage));
-                }
+                let patch: WindowPatch = serde_json::from_value(input.clone())
+                    .context("Failed to parse tool input as WindowPatch")?;
+                return Ok((patch, response.usage));
             }
         }
 
@@ -426,17 +380,114 @@ impl AnthropicClient {
         for content in &response.content {
             if content.content_type == "tool_use"
                 && content.name.as_deref() == Some("submit_speaker_identifications")
+                && let Some(input) = &content.input
             {
-                if let Some(input) = &content.input {
-                    let result: SpeakerIdToolResult = serde_json::from_value(input.clone())
-                        .context("Failed to parse tool input as SpeakerIdToolResult")?;
-                    return Ok((result.identifications, response.usage));
-                }
+                let result: SpeakerIdToolResult = serde_json::from_value(input.clone())
+                    .context("Failed to parse tool input as SpeakerIdToolResult")?;
+                return Ok((result.identifications, response.usage));
             }
         }
 
         anyhow::bail!("No tool_use response found for speaker identification")
     }
+
+    /// Send a speaker merge check request using tool use
+    pub async fn send_speaker_merge_request(
+        &self,
+        system: &str,
+        user: &str,
+    ) -> Result<(Vec<SpeakerMergeVerdict>, Usage)> {
+        let start = Instant::now();
+        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let tool = Tool {
+            name: "submit_speaker_merges".to_string(),
+            description: "Submit same-person verdicts for candidate speaker merges"
+                .to_string(),
+            input_schema: get_speaker_merge_tool_schema(),
+        };
+
+        let request = AnthropicToolRequest {
+            model: self.config.model.clone(),
+            max_tokens: self.config.max_tokens,
+            temperature: Some(self.config.temperature),
+            system: Some(system.to_string()),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: user.to_string(),
+            }],
+            tools: vec![tool],
+            tool_choice: Some(ToolChoice {
+                choice_type: "tool".to_string(),
+                name: "submit_speaker_merges".to_string(),
+            }),
+        };
+
+        let request_json = serde_json::to_value(&request).unwrap_or_default();
+
+        let response = self
+            .client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send request to Anthropic API")?;
+
+        let status_code = response.status().as_u16();
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let error_msg = format!("Anthropic API error: {} - {}", status_code, body);
+
+            self.write_log_entry("send_speaker_merge_request", &LogEntry {
+                timestamp,
+                method: "send_speaker_merge_request".to_string(),
+                duration_ms,
+                request: request_json,
+                response: None,
+                status_code: Some(status_code),
+                error: Some(error_msg.clone()),
+            });
+
+            anyhow::bail!(error_msg);
+        }
+
+        let response_bytes = response.bytes().await
+            .context("Failed to read response bytes")?;
+        let response_json: serde_json::Value = serde_json::from_slice(&response_bytes)
+            .unwrap_or_default();
+
+        self.write_log_entry("send_speaker_merge_request", &LogEntry {
+            timestamp,
+            method: "send_speaker_merge_request".to_string(),
+            duration_ms,
+            request: request_json,
+            response: Some(response_json.clone()),
+            status_code: Some(status_code),
+            error: None,
+        });
+
+        let response: AnthropicResponse = serde_json::from_value(response_json)
+            .context("Failed to parse Anthropic API response")?;
+
+        // Find the tool_use content block
+        for content in &response.content {
+            if content.content_type == "tool_use"
+                && content.name.as_deref() == Some("submit_speaker_merges")
+                && let Some(input) = &content.input
+            {
+                let result: SpeakerMergeToolResult = serde_json::from_value(input.clone())
+                    .context("Failed to parse tool input as SpeakerMergeToolResult")?;
+                return Ok((result.verdicts, response.usage));
+            }
+        }
+
+        anyhow::bail!("No tool_use response found for speaker merge check")
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -522,3 +573,9 @@ struct ContentBlock {
 struct SpeakerIdToolResult {
     identifications: Vec<SpeakerIdentification>,
 }
+
+/// Internal struct for parsing speaker merge tool response
+#[derive(Debug, Deserialize)]
+struct SpeakerMergeToolResult {
+    verdicts: Vec<SpeakerMergeVerdict>,
+}
@@ -0,0 +1,182 @@
+use serde::Serialize;
+
+use super::client::Usage;
+use super::prompts::{get_patch_tool_schema, SYSTEM_PROMPT};
+
+/// Rough characters per token for English prompt text and JSON
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Tokens the API adds to every request that forces tool use
+const TOOL_USE_OVERHEAD_TOKENS: u32 = 313;
+
+/// Output tokens for an empty `submit_patch` call
+const OUTPUT_BASE_TOKENS: u32 = 150;
+
+/// Output tokens per relabel in a `submit_patch` call
+const OUTPUT_TOKENS_PER_RELABEL: u32 = 40;
+
+/// USD price per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+impl ModelPricing {
+    /// Published pricing for a model ID, if known
+    pub fn for_model(model: &str) -> Option<Self> {
+        let (input, output) = if model.contains("haiku-4") {
+            (1.0, 5.0)
+        } else if model.contains("3-5-haiku") {
+            (0.8, 4.0)
+        } else if model.contains("haiku") {
+            (0.25, 1.25)
+        } else if model.contains("sonnet") {
+            (3.0, 15.0)
+        } else if model.contains("opus-4-5") {
+            (5.0, 25.0)
+        } else if model.contains("opus") {
+            (15.0, 75.0)
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            input_per_mtok: input,
+            output_per_mtok: output,
+        })
+    }
+
+    /// Cost in USD of the given token counts
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        input_tokens as f64 * self.input_per_mtok / 1_000_000.0
+            + output_tokens as f64 * self.output_per_mtok / 1_000_000.0
+    }
+
+    /// Cost in USD of reported API usage
+    pub fn usage_cost(&self, usage: &Usage) -> f64 {
+        self.cost(usage.input_tokens as u64, usage.output_tokens as u64)
+    }
+}
+
+impl Default for ModelPricing {
+    /// Pricing of the default model
+    fn default() -> Self {
+        Self {
+            input_per_mtok: 1.0,
+            output_per_mtok: 5.0,
+        }
+    }
+}
+
+/// Estimated token counts for one window request
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowCostEstimate {
+    pub window_id: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+impl WindowCostEstimate {
+    /// Estimate a `submit_patch` request from its user prompt and edit budget
+    pub fn new(window_id: &str, prompt: &str, edit_budget_tokens: usize) -> Self {
+        let schema = get_patch_tool_schema().to_string();
+        Self {
+            window_id: window_id.to_string(),
+            input_tokens: estimate_tokens(&SYSTEM_PROMPT)
+                + estimate_tokens(prompt)
+                + estimate_tokens(&schema)
+                + TOOL_USE_OVERHEAD_TOKENS,
+            output_tokens: OUTPUT_BASE_TOKENS
+                + OUTPUT_TOKENS_PER_RELABEL * edit_budget_tokens as u32,
+        }
+    }
+}
+
+/// Pre-flight estimate of Stage 1 token usage and cost
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimate {
+    pub model: String,
+    pub pricing: ModelPricing,
+    /// Maximum retries per window after a validation failure
+    pub max_retries: u32,
+    pub windows: Vec<WindowCostEstimate>,
+}
+
+impl CostEstimate {
+    pub fn input_tokens(&self) -> u64 {
+        self.windows.iter().map(|w| w.input_tokens as u64).sum()
+    }
+
+    pub fn output_tokens(&self) -> u64 {
+        self.windows.iter().map(|w| w.output_tokens as u64).sum()
+    }
+
+    /// Estimated cost in USD with one request per window
+    pub fn cost_usd(&self) -> f64 {
+        self.pricing.cost(self.input_tokens(), self.output_tokens())
+    }
+
+    /// Estimated cost in USD if every window used all its retries
+    pub fn worst_case_cost_usd(&self) -> f64 {
+        self.cost_usd() * (1 + self.max_retries) as f64
+    }
+
+    /// Format as a text report
+    pub fn format_text(&self) -> String {
+        let mut output = format!(
+            "Stage 1 estimate for {} ({} windows)\n",
+            self.model,
+            self.windows.len()
+        );
+        for w in &self.windows {
+            output.push_str(&format!(
+                "  {}: ~{} input, ~{} output tokens\n",
+                w.window_id, w.input_tokens, w.output_tokens
+            ));
+        }
+        output.push_str(&format!(
+            "Total: ~{} input, ~{} output tokens\n",
+            self.input_tokens(),
+            self.output_tokens()
+        ));
+        output.push_str(&format!(
+            "Estimated cost: ${:.4} (up to ${:.4} if every window retries {} times)\n",
+            self.cost_usd(),
+            self.worst_case_cost_usd(),
+            self.max_retries
+        ));
+        output
+    }
+}
+
+/// Approximate token count of a text
+pub fn estimate_tokens(text: &str) -> u32 {
+    (text.chars().count() as f64 / CHARS_PER_TOKEN).ceil() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_and_estimate() {
+        let haiku = ModelPricing::for_model("claude-haiku-4-5-20251001").unwrap();
+        assert_eq!(haiku, ModelPricing::default());
+        assert_eq!(ModelPricing::for_model("claude-opus-4-5").unwrap().output_per_mtok, 25.0);
+        assert!(ModelPricing::for_model("gpt-4").is_none());
+        assert!((haiku.cost(1_000_000, 200_000) - 2.0).abs() < 1e-9);
+
+        let window = WindowCostEstimate::new("w_0", &"x".repeat(400), 2);
+        assert!(window.input_tokens > 100 + TOOL_USE_OVERHEAD_TOKENS);
+        assert_eq!(window.output_tokens, OUTPUT_BASE_TOKENS + 2 * OUTPUT_TOKENS_PER_RELABEL);
+
+        let estimate = CostEstimate {
+            model: "claude-haiku-4-5".to_string(),
+            pricing: haiku,
+            max_retries: 2,
+            windows: vec![window],
+        };
+        assert!((estimate.worst_case_cost_usd() - 3.0 * estimate.cost_usd()).abs() < 1e-12);
+    }
+}
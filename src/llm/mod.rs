@@ -1,9 +1,13 @@
 pub mod client;
+pub mod cost;
 pub mod prompts;
 pub mod speaker_id_prompt;
+pub mod speaker_merge_prompt;
 pub mod validation;
 
 pub use client::*;
+pub use cost::*;
 pub use prompts::*;
 pub use speaker_id_prompt::*;
+pub use speaker_merge_prompt::*;
 pub use validation::*;
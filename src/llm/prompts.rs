@@ -1,7 +1,17 @@
+use std::sync::LazyLock;
+
 use crate::models::{TokenizedTranscript, Window};
 
+/// Shortest pause shown on a token in the prompt
+const PROMPT_MIN_PAUSE_MS: u64 = 300;
+
+/// Transcription confidence is shown on tokens below this
+const PROMPT_MAX_TRANSCRIPTION_CONF: f64 = 0.7;
+
 /// System prompt for the LLM (non-negotiable constraints)
-pub const SYSTEM_PROMPT: &str = r#"You are editing a diarized transcript. You MUST follow these rules:
+pub static SYSTEM_PROMPT: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        r#"You are editing a diarized transcript. You MUST follow these rules:
 
 1. You MUST NOT add, remove, or change any words.
 2. You MUST NOT change timestamps.
@@ -13,7 +23,12 @@ CONSTRAINTS:
 - You have an edit budget: you may relabel at most 3% of tokens in this window. Prefer fewer changes.
 - Use only the provided reason codes for changes.
 - Tokens marked as "anchor" are READ-ONLY and must not be changed.
+- Tokens marked as "locked" were confirmed by a human reviewer and must not be changed.
 - Minimize speaker switches while maintaining conversational coherence.
+- "overlap_flag" marks tokens spoken while another speaker is talking; "handoff_flag" marks tokens at a quick speaker change without overlap, where the boundary may be off by a word.
+- "pause_before_ms" gives the silence before a token (shown from {PROMPT_MIN_PAUSE_MS}ms) and "turn_point" marks likely turn-taking points. Speaker changes are more plausible after them and less plausible mid-phrase.
+- "speaker_conf" is missing when no speaker confidence is known; "speaker_conf_estimated" marks a confidence estimated from turn length, position and nearby speaker changes rather than given by the diarizer.
+- "transcription_conf" appears on words the speech recognizer was unsure of (shown below {PROMPT_MAX_TRANSCRIPTION_CONF}). Such words often come from crosstalk or mumbling: their wording is weak evidence, and they may belong to another speaker.
 
 REASON CODES (use only these):
 - jitter_short_turn: Short turn caused by speaker jitter
@@ -23,7 +38,76 @@ REASON CODES (use only these):
 - backchannel_attribution: Backchannel attribution (e.g., "yeah", "uh-huh")
 - do_not_change: Explicitly keeping unchanged
 
-If you violate any rule, list it in the "violations" array."#;
+If you violate any rule, list it in the "violations" array."#
+    )
+});
+
+/// Get the tool schema for window patches (`submit_patch`)
+pub fn get_patch_tool_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "window_id": {
+                "type": "string",
+                "description": "ID of the window being patched"
+            },
+            "token_relabels": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "token_id": {"type": "string"},
+                        "new_speaker": {"type": "integer"},
+                        "reason": {
+                            "type": "string",
+                            "enum": ["jitter_short_turn", "overlap_boundary", "lexical_continuity", "dialogue_pairing", "backchannel_attribution", "do_not_change"]
+                        },
+                        "confidence": {
+                            "type": "number",
+                            "minimum": 0.0,
+                            "maximum": 1.0,
+                            "description": "How confident you are in this relabel (0.0 - 1.0)"
+                        }
+                    },
+                    "required": ["token_id", "new_speaker", "reason"]
+                }
+            },
+            "turn_edits": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "type": {"type": "string", "enum": ["merge_turns", "split_turn"]},
+                        "turn_id": {"type": "string"},
+                        "to_turn_id": {"type": "string"},
+                        "split_at_token_id": {"type": "string"},
+                        "reason": {
+                            "type": "string",
+                            "enum": ["jitter_short_turn", "overlap_boundary", "lexical_continuity", "dialogue_pairing", "backchannel_attribution", "do_not_change"]
+                        }
+                    },
+                    "required": ["type", "turn_id", "reason"]
+                }
+            },
+            "violations": {
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "List any rules you may have violated"
+            },
+            "notes": {
+                "type": "object",
+                "properties": {
+                    "uncertain_tokens": {
+                        "type": "array",
+                        "items": {"type": "string"}
+                    },
+                    "summary": {"type": "string"}
+                }
+            }
+        },
+        "required": ["window_id", "token_relabels", "turn_edits", "violations", "notes"]
+    })
+}
 
 /// Build the user prompt for a window
 pub fn build_window_prompt(
@@ -62,7 +146,7 @@ pub fn build_window_prompt(
                 stats.common_words.join(", ")
             ));
         }
-        prompt.push_str("\n");
+        prompt.push('\n');
     }
 
     // Anchor prefix (read-only)
@@ -91,6 +175,7 @@ pub fn build_window_prompt(
     prompt.push_str("## Instructions\n");
     prompt.push_str("Analyze the tokens and submit a patch using the submit_patch tool.\n");
     prompt.push_str("Only relabel tokens where you are confident there is an error.\n");
+    prompt.push_str("Give each relabel a confidence (0.0 - 1.0) and list any tokens you are unsure about in notes.uncertain_tokens.\n");
     prompt.push_str("Focus on:\n");
     prompt.push_str("- Short turns that may be speaker jitter\n");
     prompt.push_str("- Backchannels attributed to the wrong speaker\n");
@@ -110,10 +195,18 @@ fn format_tokens(transcript: &TokenizedTranscript, indices: &[usize], is_anchor:
             start_ms: t.start_ms,
             end_ms: t.end_ms,
             speaker: t.speaker,
-            speaker_conf: t.speaker_conf,
+            speaker_conf: t.speaker_confidence(),
+            speaker_conf_estimated: t.is_speaker_conf_estimated(),
+            transcription_conf: (t.transcription_conf < PROMPT_MAX_TRANSCRIPTION_CONF)
+                .then_some(t.transcription_conf),
             overlap_flag: t.is_overlap_region,
+            handoff_flag: t.is_tight_handoff,
+            pause_before_ms: (t.pause_before_ms >= PROMPT_MIN_PAUSE_MS)
+                .then_some(t.pause_before_ms),
+            turn_point: t.is_turn_point,
             turn_id: t.turn_id.clone(),
             anchor: is_anchor,
+            locked: t.locked,
         })
         .collect();
 
@@ -127,11 +220,24 @@ struct TokenDisplay {
     start_ms: u64,
     end_ms: u64,
     speaker: u32,
-    speaker_conf: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speaker_conf: Option<f64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    speaker_conf_estimated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcription_conf: Option<f64>,
     overlap_flag: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    handoff_flag: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pause_before_ms: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    turn_point: bool,
     turn_id: String,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     anchor: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    locked: bool,
 }
 
 /// Compute speaker statistics for hints
@@ -154,10 +260,11 @@ fn compute_speaker_stats(
     // Compute turn durations
     for turn in &transcript.turns {
         // Check if turn overlaps with window
-        if turn.start_ms < window.end_ms && turn.end_ms > window.start_ms {
-            if let Some(entry) = stats.get_mut(&turn.speaker) {
-                entry.turn_durations.push(turn.duration_ms());
-            }
+        if turn.start_ms < window.end_ms
+            && turn.end_ms > window.start_ms
+            && let Some(entry) = stats.get_mut(&turn.speaker)
+        {
+            entry.turn_durations.push(turn.duration_ms());
         }
     }
 
@@ -178,7 +285,7 @@ fn compute_speaker_stats(
                 *word_counts.entry(word).or_insert(0) += 1;
             }
             let mut common: Vec<_> = word_counts.into_iter().collect();
-            common.sort_by(|a, b| b.1.cmp(&a.1));
+            common.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
             let common_words: Vec<String> = common
                 .into_iter()
                 .take(5)
@@ -0,0 +1,103 @@
+use crate::models::SpeakerMerge;
+
+/// Build the system prompt for checking speaker merges
+pub fn build_speaker_merge_system_prompt() -> String {
+    r#"You are an expert at reviewing speaker diarization of conversation transcripts.
+
+Diarization sometimes splits one real person into two numeric speaker IDs. You are given candidate pairs of speaker IDs that may be the same person, with excerpts and the statistical evidence behind each candidate. Decide for each pair whether both IDs are the same person.
+
+## Guidelines
+
+1. **Same person**: one ID continues the other's sentences or thoughts, they never respond to each other, they share vocabulary, role and speaking style
+2. **Different people**: they ask and answer each other, greet or address each other, disagree, or refer to each other by name
+3. **Timing**: two IDs active in different parts of the recording may be one person whose voice drifted, or two people who spoke at different times; rely on content to tell which
+
+## Important Rules
+
+- Only answer same_person = true with CLEAR evidence
+- Confidence reflects certainty in your verdict, whichever way it goes
+- Provide specific quotes or observations as evidence
+- Merging two real people is worse than leaving one person split
+
+## Output Format
+
+Use the submit_speaker_merges tool to give one verdict per candidate pair."#
+        .to_string()
+}
+
+/// Build the user prompt with speaker excerpts and candidate merges
+pub fn build_speaker_merge_user_prompt(
+    speaker_excerpts: &[(u32, Vec<String>)],
+    merges: &[SpeakerMerge],
+) -> String {
+    let mut prompt = String::new();
+
+    prompt.push_str("# Transcript Excerpts by Speaker\n\n");
+    for (speaker_id, excerpts) in speaker_excerpts {
+        prompt.push_str(&format!("## Speaker {}\n\n", speaker_id));
+        for (i, excerpt) in excerpts.iter().enumerate() {
+            prompt.push_str(&format!("**Excerpt {}:**\n{}\n\n", i + 1, excerpt));
+        }
+    }
+
+    prompt.push_str("# Candidate Merges\n\n");
+    for merge in merges {
+        prompt.push_str(&format!(
+            "## Speaker {} into Speaker {} (score {:.2})\n\n",
+            merge.from_speaker, merge.to_speaker, merge.confidence
+        ));
+        for evidence in &merge.evidence {
+            prompt.push_str(&format!("- {}\n", evidence));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("# Task\n\n");
+    prompt.push_str("Decide for each candidate whether both speaker IDs are the same person. ");
+    prompt.push_str("Use the submit_speaker_merges tool to provide your verdicts with confidence scores and evidence.\n");
+
+    prompt
+}
+
+/// Get the tool schema for speaker merge verdicts
+pub fn get_speaker_merge_tool_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "verdicts": {
+                "type": "array",
+                "description": "Verdict for each candidate merge",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "from_speaker": {
+                            "type": "integer",
+                            "description": "The speaker ID that would be relabeled"
+                        },
+                        "to_speaker": {
+                            "type": "integer",
+                            "description": "The speaker ID it would be merged into"
+                        },
+                        "same_person": {
+                            "type": "boolean",
+                            "description": "Whether both speaker IDs are the same person"
+                        },
+                        "confidence": {
+                            "type": "number",
+                            "description": "Confidence in the verdict from 0.0 to 1.0",
+                            "minimum": 0.0,
+                            "maximum": 1.0
+                        },
+                        "evidence": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Specific quotes or observations supporting the verdict"
+                        }
+                    },
+                    "required": ["from_speaker", "to_speaker", "same_person", "confidence", "evidence"]
+                }
+            }
+        },
+        "required": ["verdicts"]
+    })
+}
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::models::{PatchValidation, TokenizedTranscript, Window, WindowPatch};
+use crate::models::{PatchValidation, TokenizedTranscript, Window, WindowPatch, WindowQuality};
 
 /// Configuration for patch validation
 #[derive(Debug, Clone)]
@@ -57,6 +57,19 @@ pub fn validate_patch(
         }
     }
 
+    // 2b. Check no locked (human-reviewed) token is relabeled
+    for relabel in &patch.token_relabels {
+        if transcript
+            .get_token(&relabel.token_id)
+            .is_some_and(|t| t.locked && t.speaker != relabel.new_speaker)
+        {
+            errors.push(format!(
+                "Token {} is locked by human review",
+                relabel.token_id
+            ));
+        }
+    }
+
     // 3. Check all new speakers are allowed
     let allowed: HashSet<u32> = config.allowed_speakers.iter().cloned().collect();
     for relabel in &patch.token_relabels {
@@ -107,7 +120,18 @@ pub fn validate_patch(
     }
 
     if errors.is_empty() {
-        PatchValidation::valid(edit_budget_used)
+        let budget_margin = if edit_budget > 0 {
+            1.0 - edit_count as f64 / edit_budget as f64
+        } else {
+            1.0
+        };
+        PatchValidation::valid(
+            edit_budget_used,
+            WindowQuality {
+                budget_margin,
+                cost_delta: cost_increase,
+            },
+        )
     } else {
         PatchValidation::invalid(errors)
     }
@@ -134,10 +158,11 @@ fn compute_cost(transcript: &TokenizedTranscript, window: &Window) -> f64 {
 
     // Count short turns overlapping window
     for turn in &transcript.turns {
-        if turn.start_ms < window.end_ms && turn.end_ms > window.start_ms {
-            if turn.duration_ms() < 700 {
-                short_turns += 1;
-            }
+        if turn.start_ms < window.end_ms
+            && turn.end_ms > window.start_ms
+            && turn.duration_ms() < 700
+        {
+            short_turns += 1;
         }
     }
 
@@ -193,7 +218,7 @@ fn compute_cost_after_patch(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{PatchNotes, ReasonCode, TokenRelabel};
+    use crate::models::PatchNotes;
 
     #[test]
     fn test_validate_empty_patch() {
@@ -203,6 +228,7 @@ mod tests {
             turn_edits: vec![],
             violations: vec![],
             notes: PatchNotes::default(),
+            quality: WindowQuality::default(),
         };
 
         // Create minimal transcript and window
@@ -221,6 +247,7 @@ mod tests {
             anchor_suffix_indices: vec![],
             is_problem_zone: false,
             problem_types: vec![],
+            severity: 0.0,
         };
 
         let config = ValidationConfig::default();
@@ -237,6 +264,7 @@ mod tests {
             turn_edits: vec![],
             violations: vec!["I changed a word".to_string()],
             notes: PatchNotes::default(),
+            quality: WindowQuality::default(),
         };
 
         let transcript = TokenizedTranscript {
@@ -254,6 +282,7 @@ mod tests {
             anchor_suffix_indices: vec![],
             is_problem_zone: false,
             problem_types: vec![],
+            severity: 0.0,
         };
 
         let config = ValidationConfig::default();
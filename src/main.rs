@@ -1,15 +1,21 @@
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, FixedOffset};
+use clap::{Args, Parser, Subcommand};
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
+use diatribe::llm::DEFAULT_MODEL;
 use diatribe::{
-    apply_heuristics, execute_speaker_id, execute_stage1, execute_stage2, execute_stage3,
-    normalize, parse_deepgram_file, parse_participants_file,
-    AnthropicClient, AnthropicConfig, HeuristicsConfig, Participant, ProblemZoneConfig,
-    SpeakerIdConfig, Stage1Config, Stage2Config, Stage3Config, TranscriptMetadata, WindowConfig,
+    apply_heuristics, apply_review_decisions, estimate_stage1, execute_speaker_id,
+    execute_speaker_merge, execute_stage1, execute_stage2, execute_stage3, normalize,
+    parse_deepgram_file, parse_participants_file, read_review_decisions, AnalysisFormat,
+    AnthropicClient, AnthropicConfig, ChangeSet, HeuristicsConfig, HumanFormat,
+    HumanRenderOptions, ModelPricing, Participant, ProblemZoneConfig, ReconciliationStrategy,
+    ReviewExport, SpeakerIdConfig, SpeakerMergeConfig, Stage1Budget, Stage1Config, Stage2Config,
+    Stage3Config, Stage3Outputs, TimestampFormat, TranscriptAnalysis, TranscriptDiff,
+    TranscriptMetadata, UnidentifiedLabel, WindowConfig, WindowStrategy,
 };
 
 #[derive(Parser)]
@@ -20,7 +26,111 @@ struct Cli {
     command: Commands,
 }
 
+/// Layout options for the human-readable transcript
+#[derive(Args)]
+struct HumanArgs {
+    /// Human transcript layout: text, markdown, chat or legal
+    #[arg(long, default_value = "text")]
+    human_format: HumanFormat,
+
+    /// Timestamp style: millis (MM:SS.mmm) or seconds (HH:MM:SS)
+    #[arg(long, default_value = "millis")]
+    timestamp_format: TimestampFormat,
+
+    /// Split turns longer than this many words into paragraphs
+    #[arg(long)]
+    paragraph_words: Option<usize>,
+
+    /// Label for speakers without a name: numbered ("Speaker N") or unknown
+    #[arg(long, default_value = "numbered")]
+    unidentified_label: UnidentifiedLabel,
+
+    /// Recording start as an RFC 3339 datetime (e.g. 2024-03-01T09:30:00-05:00);
+    /// timestamps are rendered as wall-clock times in its time zone
+    #[arg(long)]
+    recording_start: Option<DateTime<FixedOffset>>,
+
+    /// Offset in milliseconds added to every timestamp, for transcripts cut
+    /// from a longer recording
+    #[arg(long, default_value = "0")]
+    timestamp_offset_ms: u64,
+}
+
+impl HumanArgs {
+    fn render_options(&self) -> HumanRenderOptions {
+        HumanRenderOptions {
+            format: self.human_format,
+            timestamp_format: self.timestamp_format,
+            max_paragraph_words: self.paragraph_words,
+            unidentified_label: self.unidentified_label,
+            recording_start: self.recording_start,
+            offset_ms: self.timestamp_offset_ms,
+            ..Default::default()
+        }
+    }
+}
+
+/// Window placement and sizing options
+#[derive(Args)]
+struct WindowArgs {
+    /// Window size in milliseconds
+    #[arg(long, default_value = "45000")]
+    window_size_ms: u64,
+
+    /// Window stride in milliseconds
+    #[arg(long, default_value = "15000")]
+    window_stride_ms: u64,
+
+    /// Window placement: sliding (fixed size and stride) or adaptive (one
+    /// window per cluster of problem zones, cut at turn boundaries)
+    #[arg(long, default_value = "sliding")]
+    window_strategy: WindowStrategy,
+
+    /// Split windows with more editable tokens than this
+    #[arg(long)]
+    max_window_tokens: Option<usize>,
+
+    /// Merge windows with fewer editable tokens than this into a neighbour
+    #[arg(long)]
+    min_window_tokens: Option<usize>,
+
+    /// Split windows whose estimated Stage 1 prompt exceeds this many tokens
+    #[arg(long)]
+    max_prompt_tokens: Option<u32>,
+
+    /// Anchor context size in milliseconds (before and after each window)
+    #[arg(long, default_value = "5000")]
+    anchor_size_ms: u64,
+
+    /// Extend anchors to at least this many tokens
+    #[arg(long)]
+    min_anchor_tokens: Option<usize>,
+
+    /// Cut anchors to at most this many tokens
+    #[arg(long)]
+    max_anchor_tokens: Option<usize>,
+}
+
+impl WindowArgs {
+    fn window_config(&self) -> WindowConfig {
+        WindowConfig {
+            window_size_ms: self.window_size_ms,
+            stride_ms: self.window_stride_ms,
+            anchor_size_ms: self.anchor_size_ms,
+            filter_problem_zones: true,
+            strategy: self.window_strategy,
+            max_window_tokens: self.max_window_tokens,
+            min_window_tokens: self.min_window_tokens,
+            max_prompt_tokens: self.max_prompt_tokens,
+            min_anchor_tokens: self.min_anchor_tokens,
+            max_anchor_tokens: self.max_anchor_tokens,
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Process a diarized transcript to improve speaker attribution
     Process {
@@ -36,21 +146,48 @@ enum Commands {
         #[arg(long)]
         human_readable: Option<PathBuf>,
 
+        #[command(flatten)]
+        human_args: HumanArgs,
+
+        /// Output file for the original vs. corrected diff (HTML if the path
+        /// ends in .html, otherwise text)
+        #[arg(long)]
+        diff: Option<PathBuf>,
+
+        /// Output file for an interactive, self-contained HTML report
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Output file for speaker analytics on the corrected transcript
+        /// (JSON if the path ends in .json, otherwise text)
+        #[arg(long)]
+        analytics: Option<PathBuf>,
+
         /// Maximum number of speakers
         #[arg(long, default_value = "4")]
         max_speakers: u32,
 
+        /// Number of real speakers; the smallest extra speakers are folded
+        /// into the most similar remaining speaker unless they hold a large
+        /// share of the talk time
+        #[arg(long)]
+        expected_speakers: Option<usize>,
+
+        /// Merge speaker IDs that look like one person across the whole
+        /// recording (checked by the LLM unless --heuristics-only)
+        #[arg(long)]
+        merge_speakers: bool,
+
+        /// Minimum confidence (0.0-1.0) to apply a speaker merge
+        #[arg(long, default_value = "0.75")]
+        merge_confidence: f64,
+
         /// Edit budget as percentage of tokens (0-100)
         #[arg(long, default_value = "3.0")]
         edit_budget: f64,
 
-        /// Window size in milliseconds
-        #[arg(long, default_value = "45000")]
-        window_size_ms: u64,
-
-        /// Window stride in milliseconds
-        #[arg(long, default_value = "15000")]
-        window_stride_ms: u64,
+        #[command(flatten)]
+        window_args: WindowArgs,
 
         /// Minimum turn duration in milliseconds
         #[arg(long, default_value = "700")]
@@ -60,6 +197,54 @@ enum Commands {
         #[arg(long)]
         heuristics_only: bool,
 
+        /// Reconcile with global Viterbi smoothing instead of per-token voting
+        #[arg(long)]
+        smoothing: bool,
+
+        /// Only send the N most severe problem windows to the LLM
+        #[arg(long)]
+        max_windows: Option<usize>,
+
+        /// Only send problem windows with at least this severity (0.0-1.0)
+        #[arg(long)]
+        min_severity: Option<f64>,
+
+        /// Estimate Stage 1 token usage and cost, print it and exit without
+        /// calling the API
+        #[arg(long)]
+        estimate: bool,
+
+        /// Abort before Stage 1 if its estimated cost in USD exceeds this
+        #[arg(long)]
+        max_cost: Option<f64>,
+
+        /// Stop sending windows once Stage 1 has used this many input tokens
+        #[arg(long)]
+        max_input_tokens: Option<u64>,
+
+        /// Stop sending windows once Stage 1 has used this many output tokens
+        #[arg(long)]
+        max_output_tokens: Option<u64>,
+
+        /// Stop sending windows once Stage 1 has spent this many USD
+        #[arg(long)]
+        budget_usd: Option<f64>,
+
+        /// Write the proposed changes to --output as a change set instead of
+        /// a corrected transcript (diff goes to --human-readable or stdout)
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Export uncertain tokens, conflicts and rejected windows for human
+        /// review (CSV if the path ends in .csv, otherwise JSON)
+        #[arg(long)]
+        review_export: Option<PathBuf>,
+
+        /// Reviewer decisions (JSON or filled-in review CSV) to apply as
+        /// locked labels before processing
+        #[arg(long)]
+        review_decisions: Option<PathBuf>,
+
         /// Comma-separated list of participant names for speaker identification
         #[arg(long, value_delimiter = ',')]
         participants: Option<Vec<String>>,
@@ -81,12 +266,55 @@ enum Commands {
         log_dir: Option<PathBuf>,
     },
 
+    /// Apply a change set produced by `process --dry-run`
+    Apply {
+        /// Input transcript file (Deepgram JSON format)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Change set file (JSON)
+        #[arg(short, long)]
+        changes: PathBuf,
+
+        /// Output file for machine-readable transcript (JSON)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Output file for human-readable transcript (text)
+        #[arg(long)]
+        human_readable: Option<PathBuf>,
+
+        #[command(flatten)]
+        human_args: HumanArgs,
+
+        /// Output file for the original vs. corrected diff (HTML if the path
+        /// ends in .html, otherwise text)
+        #[arg(long)]
+        diff: Option<PathBuf>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Analyze a transcript without making changes
     Analyze {
         /// Input transcript file (Deepgram JSON format)
         #[arg(short, long)]
         input: PathBuf,
 
+        /// Report format: text or json (problem zones, windows, speaker stats)
+        #[arg(long, default_value = "text")]
+        format: AnalysisFormat,
+
+        #[command(flatten)]
+        window_args: WindowArgs,
+
+        /// Also write speaker analytics to a file (JSON if the path ends in
+        /// .json, otherwise text)
+        #[arg(long)]
+        analytics: Option<PathBuf>,
+
         /// Verbose output
         #[arg(short, long)]
         verbose: bool,
@@ -102,12 +330,29 @@ async fn main() -> Result<()> {
             input,
             output,
             human_readable,
+            human_args,
+            diff,
+            report,
+            analytics,
             max_speakers,
+            expected_speakers,
+            merge_speakers,
+            merge_confidence,
             edit_budget,
-            window_size_ms,
-            window_stride_ms,
+            window_args,
             min_turn_ms,
             heuristics_only,
+            smoothing,
+            max_windows,
+            min_severity,
+            estimate,
+            max_cost,
+            max_input_tokens,
+            max_output_tokens,
+            budget_usd,
+            dry_run,
+            review_export,
+            review_decisions,
             participants,
             participants_file,
             speaker_id_confidence,
@@ -119,12 +364,31 @@ async fn main() -> Result<()> {
                 input,
                 output,
                 human_readable,
+                human_args.render_options(),
+                diff,
+                report,
+                analytics,
                 max_speakers,
+                expected_speakers,
+                merge_speakers,
+                merge_confidence,
                 edit_budget,
-                window_size_ms,
-                window_stride_ms,
+                window_args.window_config(),
                 min_turn_ms,
                 heuristics_only,
+                smoothing,
+                max_windows,
+                min_severity,
+                estimate,
+                max_cost,
+                Stage1Budget {
+                    max_input_tokens,
+                    max_output_tokens,
+                    max_cost_usd: budget_usd,
+                },
+                dry_run,
+                review_export,
+                review_decisions,
                 participants,
                 participants_file,
                 speaker_id_confidence,
@@ -132,29 +396,74 @@ async fn main() -> Result<()> {
             )
             .await
         }
-        Commands::Analyze { input, verbose } => {
+        Commands::Apply {
+            input,
+            changes,
+            output,
+            human_readable,
+            human_args,
+            diff,
+            verbose,
+        } => {
+            setup_logging(verbose);
+            apply_change_set(
+                input,
+                changes,
+                output,
+                human_readable,
+                human_args.render_options(),
+                diff,
+            )
+        }
+        Commands::Analyze {
+            input,
+            format,
+            window_args,
+            analytics,
+            verbose,
+        } => {
             setup_logging(verbose);
-            analyze_transcript(input)
+            analyze_transcript(input, format, window_args.window_config(), analytics)
         }
     }
 }
 
 fn setup_logging(verbose: bool) {
     let level = if verbose { Level::DEBUG } else { Level::INFO };
-    let subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    // Log to stderr so reports written to stdout stay machine-readable
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .finish();
     tracing::subscriber::set_global_default(subscriber).ok();
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_transcript(
     input: PathBuf,
     output: PathBuf,
     human_readable: Option<PathBuf>,
+    human_options: HumanRenderOptions,
+    diff: Option<PathBuf>,
+    report: Option<PathBuf>,
+    analytics: Option<PathBuf>,
     max_speakers: u32,
+    expected_speakers: Option<usize>,
+    merge_speakers: bool,
+    merge_confidence: f64,
     edit_budget: f64,
-    window_size_ms: u64,
-    window_stride_ms: u64,
+    window_config: WindowConfig,
     min_turn_ms: u64,
     heuristics_only: bool,
+    smoothing: bool,
+    max_windows: Option<usize>,
+    min_severity: Option<f64>,
+    estimate: bool,
+    max_cost: Option<f64>,
+    budget: Stage1Budget,
+    dry_run: bool,
+    review_export: Option<PathBuf>,
+    review_decisions: Option<PathBuf>,
     participants: Option<Vec<String>>,
     participants_file: Option<PathBuf>,
     speaker_id_confidence: f64,
@@ -174,14 +483,20 @@ async fn process_transcript(
     // Save original speakers for comparison
     let original_speakers: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
 
+    // Reviewer decisions are locked before any stage runs
+    if let Some(path) = &review_decisions {
+        let decisions =
+            read_review_decisions(path).context("Failed to read review decisions")?;
+        let changed = apply_review_decisions(&mut transcript, &decisions)?;
+        info!(
+            "Applied {} review decisions ({} labels changed, all locked)",
+            decisions.len(),
+            changed
+        );
+    }
+
     // Stage 0: Normalize and detect problem zones
     info!("Stage 0: Normalizing transcript...");
-    let window_config = WindowConfig {
-        window_size_ms,
-        stride_ms: window_stride_ms,
-        anchor_size_ms: 5000,
-        filter_problem_zones: true,
-    };
     let problem_config = ProblemZoneConfig {
         min_turn_duration_ms: min_turn_ms,
         ..Default::default()
@@ -189,96 +504,251 @@ async fn process_transcript(
     let norm_result = normalize(&mut transcript, &window_config, &problem_config);
 
     info!(
-        "Found {} problem zones, {} windows ({} need processing)",
+        "Found {} problem zones in {} regions, {} windows ({} need processing)",
         norm_result.problem_zones.len(),
+        norm_result.merged_zones.len(),
         norm_result.windows.total_windows(),
         norm_result.windows.problem_window_count()
     );
 
     // Apply heuristics
     info!("Applying heuristics...");
-    let heuristics_config = HeuristicsConfig::default();
+    let heuristics_config = HeuristicsConfig {
+        expected_speakers,
+        ..Default::default()
+    };
     let heuristics_result = apply_heuristics(&mut transcript, &heuristics_config);
+    for fold in &heuristics_result.speaker_folds {
+        info!(
+            "Folded speaker {} into speaker {} (similarity {:.2}, {} tokens)",
+            fold.from_speaker, fold.to_speaker, fold.similarity, fold.tokens_relabeled
+        );
+    }
     info!(
         "Heuristics: {} tokens relabeled, needs_llm={}",
         heuristics_result.tokens_relabeled, heuristics_result.needs_llm
     );
 
     let mut windows_processed = 0;
+    let mut review = review_export.as_ref().map(|_| ReviewExport::new(8));
+    let run_llm = !heuristics_only && heuristics_result.needs_llm;
+
+    let stage1_config = Stage1Config {
+        edit_budget_percent: edit_budget,
+        validation: diatribe::llm::ValidationConfig {
+            max_edit_budget_percent: edit_budget,
+            allowed_speakers: (0..max_speakers).collect(),
+            ..Default::default()
+        },
+        budget,
+        max_windows,
+        min_severity,
+        ..Default::default()
+    };
+
+    // Pre-flight estimate: no API key needed, nothing is written
+    if estimate {
+        if run_llm {
+            let model = AnthropicConfig::from_env()
+                .map(|c| c.model)
+                .unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+            let cost_estimate =
+                estimate_stage1(&transcript, &norm_result.windows, &stage1_config, &model);
+            print!("{}", cost_estimate.format_text());
+        } else {
+            println!("Stage 1 would not run: no LLM calls needed");
+        }
+        return Ok(());
+    }
 
-    // Stage 1 & 2: LLM processing (if not heuristics-only)
-    if !heuristics_only && heuristics_result.needs_llm {
+    // Stage 1: LLM processing (if not heuristics-only)
+    let stage1_result = if run_llm {
         info!("Stage 1: LLM relabeling...");
 
         let api_config = AnthropicConfig::from_env()?;
-        let client = AnthropicClient::new(api_config, log_dir.clone());
+        let pricing = ModelPricing::for_model(&api_config.model).unwrap_or_default();
 
-        let stage1_config = Stage1Config {
-            edit_budget_percent: edit_budget,
-            validation: diatribe::llm::ValidationConfig {
-                max_edit_budget_percent: edit_budget,
-                allowed_speakers: (0..max_speakers).collect(),
-                ..Default::default()
-            },
-            ..Default::default()
-        };
+        if let Some(max_cost) = max_cost {
+            let cost_estimate = estimate_stage1(
+                &transcript,
+                &norm_result.windows,
+                &stage1_config,
+                &api_config.model,
+            );
+            info!(
+                "Estimated Stage 1 cost: ${:.4} (budget ${:.4})",
+                cost_estimate.cost_usd(),
+                max_cost
+            );
+            if cost_estimate.cost_usd() > max_cost {
+                anyhow::bail!(
+                    "Estimated Stage 1 cost ${:.4} exceeds --max-cost ${:.4} ({} windows)",
+                    cost_estimate.cost_usd(),
+                    max_cost,
+                    cost_estimate.windows.len()
+                );
+            }
+        }
+
+        let client = AnthropicClient::new(api_config, log_dir.clone());
 
         let stage1_result =
             execute_stage1(&client, &transcript, &norm_result.windows, &stage1_config).await?;
 
         info!(
-            "Stage 1: {} windows processed, {} patches, {} failures",
+            "Stage 1: {} windows sent ({} with changes, {} rejected), {} skipped by budget, \
+             {} filtered by severity, {} without problems",
             stage1_result.windows_processed,
-            stage1_result.patches.len(),
-            stage1_result.validation_failures
+            stage1_result.patches.iter().filter(|p| !p.is_empty()).count(),
+            stage1_result.validation_failures,
+            stage1_result.budget_skipped_windows.len(),
+            stage1_result.windows_filtered,
+            stage1_result.windows_skipped
         );
         info!(
             "API usage: {} input tokens, {} output tokens",
             stage1_result.usage.input_tokens,
             stage1_result.usage.output_tokens
         );
-        let cost = (stage1_result.usage.input_tokens as f64 * 1.0 / 1_000_000.0)
-            + (stage1_result.usage.output_tokens as f64 * 5.0 / 1_000_000.0);
-        info!("Estimated cost: ${:.4}", cost);
+        info!("Cost: ${:.4}", pricing.usage_cost(&stage1_result.usage));
 
         windows_processed = stage1_result.windows_processed;
+        Some(stage1_result)
+    } else {
+        if heuristics_only {
+            info!("Skipping LLM processing (--heuristics-only)");
+        } else {
+            info!("Skipping LLM processing (heuristics sufficient)");
+        }
+        None
+    };
 
-        // Stage 2: Reconciliation
-        if !stage1_result.patches.is_empty() {
-            info!("Stage 2: Reconciling patches...");
-            let stage2_config = Stage2Config {
-                min_turn_duration_ms: min_turn_ms,
-                ..Default::default()
-            };
-            let stage2_result = execute_stage2(
-                &mut transcript,
-                &norm_result.windows,
-                &stage1_result.patches,
-                &stage2_config,
+    // Stage 2: Reconciliation. Viterbi smoothing also runs without patches,
+    // using speaker confidence and heuristic changes alone.
+    let patches = stage1_result.as_ref().map_or(&[][..], |r| r.patches.as_slice());
+    let mut conflict_token_ids = Vec::new();
+    if smoothing || patches.iter().any(|p| !p.is_empty()) {
+        info!("Stage 2: Reconciling patches...");
+        let stage2_config = Stage2Config {
+            min_turn_duration_ms: min_turn_ms,
+            strategy: if smoothing {
+                ReconciliationStrategy::Viterbi
+            } else {
+                ReconciliationStrategy::WeightedVote
+            },
+            ..Default::default()
+        };
+        let stage2_result = execute_stage2(
+            &mut transcript,
+            &norm_result.windows,
+            patches,
+            &heuristics_result.changed_indices,
+            &stage2_config,
+        );
+        info!(
+            "Stage 2: {} tokens relabeled, {} conflicts resolved, {} constraint changes",
+            stage2_result.tokens_relabeled,
+            stage2_result.conflicts_resolved,
+            stage2_result.constraint_changes.len()
+        );
+        for change in &stage2_result.constraint_changes {
+            info!(
+                "  {:?}: token {} speaker {} -> {} ({:?})",
+                change.constraint,
+                change.token_index,
+                change.from_speaker,
+                change.to_speaker,
+                change.action
             );
+        }
+        conflict_token_ids = stage2_result.conflict_token_ids;
+    }
+
+    if let (Some(review), Some(stage1_result)) = (review.as_mut(), &stage1_result) {
+        review.add_uncertain_tokens(&transcript, &stage1_result.patches);
+        review.add_conflicts(&transcript, &conflict_token_ids);
+        for rejected in &stage1_result.rejected_windows {
+            if let Some(window) = norm_result
+                .windows
+                .windows
+                .iter()
+                .find(|w| w.window_id == rejected.window_id)
+            {
+                review.add_rejected_window(&transcript, window, &rejected.error);
+            }
+        }
+    }
+
+    // Global speaker merge across the whole recording
+    let mut speaker_merges = Vec::new();
+    if merge_speakers {
+        info!("Checking for speakers split across IDs...");
+        let merge_config = SpeakerMergeConfig {
+            min_confidence: merge_confidence,
+            ..Default::default()
+        };
+        let client = if heuristics_only {
+            None
+        } else {
+            Some(AnthropicClient::new(AnthropicConfig::from_env()?, log_dir.clone()))
+        };
+        let merge_result =
+            execute_speaker_merge(client.as_ref(), &mut transcript, &merge_config).await?;
+        for merge in &merge_result.merges {
             info!(
-                "Stage 2: {} tokens relabeled, {} conflicts resolved",
-                stage2_result.tokens_relabeled, stage2_result.conflicts_resolved
+                "  Speaker {} -> {} (confidence {:.2}, {}): {}",
+                merge.from_speaker,
+                merge.to_speaker,
+                merge.confidence,
+                if merge.applied { "merged" } else { "kept" },
+                merge.evidence.join("; ")
             );
         }
-    } else if heuristics_only {
-        info!("Skipping LLM processing (--heuristics-only)");
-    } else {
-        info!("Skipping LLM processing (heuristics sufficient)");
+        info!(
+            "Speaker merge: {} of {} proposals applied, {} tokens relabeled",
+            merge_result.merges.iter().filter(|m| m.applied).count(),
+            merge_result.merges.len(),
+            merge_result.tokens_relabeled
+        );
+        if client.is_some() {
+            info!(
+                "API usage: {} input tokens, {} output tokens",
+                merge_result.usage.input_tokens, merge_result.usage.output_tokens
+            );
+        }
+        speaker_merges = merge_result.merges;
+    }
+
+    if let (Some(review), Some(path)) = (&review, &review_export) {
+        review.write_file(path)?;
+        info!("Review export: {} items written to {:?}", review.items.len(), path);
+    }
+
+    if dry_run {
+        let change_set = ChangeSet::from_transcript(&transcript, &original_speakers)?;
+        change_set.write_json(&output)?;
+        info!("Dry run: {} proposed changes written to {:?}", change_set.len(), output);
+        match human_readable {
+            Some(path) => {
+                change_set.write_diff(&path, &human_options)?;
+                info!("Diff written to {:?}", path);
+            }
+            None => print!("{}", change_set.format_diff(&human_options)),
+        }
+        if let Some(path) = &diff {
+            TranscriptDiff::new(&transcript, &original_speakers)
+                .with_options(human_options)
+                .write_file(path)?;
+            info!("Side-by-side diff written to {:?}", path);
+        }
+        return Ok(());
     }
 
     // Parse participants for speaker identification
     let parsed_participants: Option<Vec<Participant>> = if let Some(file) = participants_file {
         Some(parse_participants_file(&file).context("Failed to parse participants file")?)
-    } else if let Some(names) = participants {
-        Some(
-            names
-                .iter()
-                .map(|n| Participant::new(n.trim()))
-                .collect(),
-        )
     } else {
-        None
+        participants.map(|names| names.iter().map(|n| Participant::new(n.trim())).collect())
     };
 
     // Speaker Identification (optional, if participants provided)
@@ -349,15 +819,25 @@ async fn process_transcript(
             .count(),
         duration_ms: transcript.duration_ms(),
         windows_processed,
+        speaker_merges,
     };
 
-    let stage3_config = Stage3Config::default();
+    let stage3_config = Stage3Config {
+        human: human_options,
+        ..Default::default()
+    };
     let stage3_result = execute_stage3(
         &transcript,
         &original_speakers,
+        &norm_result.problem_zones,
         metadata,
-        Some(&output),
-        human_readable.as_deref(),
+        &Stage3Outputs {
+            machine: Some(&output),
+            human: human_readable.as_deref(),
+            diff: diff.as_deref(),
+            report: report.as_deref(),
+            analytics: analytics.as_deref(),
+        },
         &stage3_config,
         speaker_names.as_ref(),
         speaker_identifications,
@@ -367,6 +847,15 @@ async fn process_transcript(
     if let Some(human_path) = stage3_result.human_path {
         info!("Human-readable output written to {:?}", human_path);
     }
+    if let Some(diff_path) = stage3_result.diff_path {
+        info!("Diff written to {:?}", diff_path);
+    }
+    if let Some(report_path) = stage3_result.report_path {
+        info!("HTML report written to {:?}", report_path);
+    }
+    if let Some(analytics_path) = stage3_result.analytics_path {
+        info!("Speaker analytics written to {:?}", analytics_path);
+    }
 
     // Summary
     let relabeled = transcript
@@ -389,95 +878,91 @@ async fn process_transcript(
     Ok(())
 }
 
-fn analyze_transcript(input: PathBuf) -> Result<()> {
-    info!("Analyzing transcript from {:?}", input);
+fn apply_change_set(
+    input: PathBuf,
+    changes: PathBuf,
+    output: PathBuf,
+    human_readable: Option<PathBuf>,
+    human_options: HumanRenderOptions,
+    diff: Option<PathBuf>,
+) -> Result<()> {
+    info!("Loading transcript from {:?}", input);
     let mut transcript =
         parse_deepgram_file(&input).context("Failed to parse input transcript")?;
+    let change_set = ChangeSet::read_json(&changes).context("Failed to read change set")?;
 
-    println!("Transcript Analysis");
-    println!("==================");
-    println!("Total tokens: {}", transcript.tokens.len());
-    println!("Total turns: {}", transcript.turns.len());
-    println!("Speakers: {:?}", transcript.speakers);
-    println!(
-        "Duration: {:.1}s",
-        transcript.duration_ms() as f64 / 1000.0
-    );
-    println!();
+    if change_set.total_tokens != transcript.tokens.len() {
+        anyhow::bail!(
+            "Change set was computed on {} tokens but the transcript has {}",
+            change_set.total_tokens,
+            transcript.tokens.len()
+        );
+    }
 
-    // Detect problem zones
-    let window_config = WindowConfig::default();
-    let problem_config = ProblemZoneConfig::default();
-    let norm_result = normalize(&mut transcript, &window_config, &problem_config);
+    let original_speakers: Vec<u32> = transcript.tokens.iter().map(|t| t.speaker).collect();
+    let applied = change_set.apply(&mut transcript)?;
+    info!("Applied {} of {} changes", applied, change_set.len());
 
-    println!("Problem Zones");
-    println!("-------------");
-    let mut jitter = 0;
-    let mut short_turns = 0;
-    let mut overlap = 0;
-    let mut low_conf = 0;
-
-    for zone in &norm_result.problem_zones {
-        match zone.problem_type {
-            diatribe::models::ProblemType::SpeakerJitter => jitter += 1,
-            diatribe::models::ProblemType::ShortTurn => short_turns += 1,
-            diatribe::models::ProblemType::OverlapAdjacent => overlap += 1,
-            diatribe::models::ProblemType::LowConfidence => low_conf += 1,
-        }
+    let metadata = TranscriptMetadata {
+        total_tokens: transcript.tokens.len(),
+        total_turns: transcript.turns.len(),
+        tokens_relabeled: applied,
+        duration_ms: transcript.duration_ms(),
+        windows_processed: 0,
+        speaker_merges: Vec::new(),
+    };
+
+    let stage3_result = execute_stage3(
+        &transcript,
+        &original_speakers,
+        &[],
+        metadata,
+        &Stage3Outputs {
+            machine: Some(&output),
+            human: human_readable.as_deref(),
+            diff: diff.as_deref(),
+            report: None,
+            analytics: None,
+        },
+        &Stage3Config {
+            human: human_options,
+            ..Default::default()
+        },
+        None,
+        None,
+    )?;
+
+    info!("Output written to {:?}", stage3_result.machine_path);
+    if let Some(human_path) = stage3_result.human_path {
+        info!("Human-readable output written to {:?}", human_path);
+    }
+    if let Some(diff_path) = stage3_result.diff_path {
+        info!("Diff written to {:?}", diff_path);
     }
 
-    println!("Speaker jitter zones: {}", jitter);
-    println!("Short turn zones: {}", short_turns);
-    println!("Overlap-adjacent zones: {}", overlap);
-    println!("Low confidence zones: {}", low_conf);
-    println!();
-
-    println!("Windows");
-    println!("-------");
-    println!("Total windows: {}", norm_result.windows.total_windows());
-    println!(
-        "Problem windows: {}",
-        norm_result.windows.problem_window_count()
-    );
+    Ok(())
+}
 
-    // Speaker stats
-    println!();
-    println!("Speaker Statistics");
-    println!("------------------");
-    for speaker in &transcript.speakers {
-        let word_count = transcript
-            .tokens
-            .iter()
-            .filter(|t| t.speaker == *speaker)
-            .count();
-        let turn_count = transcript
-            .turns
-            .iter()
-            .filter(|t| t.speaker == *speaker)
-            .count();
-        let avg_turn_duration = if turn_count > 0 {
-            transcript
-                .turns
-                .iter()
-                .filter(|t| t.speaker == *speaker)
-                .map(|t| t.duration_ms())
-                .sum::<u64>()
-                / turn_count as u64
-        } else {
-            0
-        };
-        let avg_confidence = transcript
-            .tokens
-            .iter()
-            .filter(|t| t.speaker == *speaker)
-            .map(|t| t.speaker_conf)
-            .sum::<f64>()
-            / word_count.max(1) as f64;
-
-        println!(
-            "Speaker {}: {} words, {} turns, avg turn {:.0}ms, avg conf {:.2}",
-            speaker, word_count, turn_count, avg_turn_duration, avg_confidence
-        );
+fn analyze_transcript(
+    input: PathBuf,
+    format: AnalysisFormat,
+    window_config: WindowConfig,
+    analytics_path: Option<PathBuf>,
+) -> Result<()> {
+    info!("Analyzing transcript from {:?}", input);
+    let mut transcript =
+        parse_deepgram_file(&input).context("Failed to parse input transcript")?;
+
+    // Detect problem zones and build windows
+    let problem_config = ProblemZoneConfig::default();
+    let norm_result = normalize(&mut transcript, &window_config, &problem_config);
+
+    let analysis = TranscriptAnalysis::new(&transcript, &norm_result);
+    print!("{}", analysis.format(format)?);
+
+    if let Some(path) = &analytics_path {
+        analysis.speaker_stats.write_file(path)?;
+        info!("Speaker analytics written to {:?}", path);
     }
 
     Ok(())
@@ -1,11 +1,17 @@
 pub mod deepgram;
 pub mod patch;
+pub mod provenance;
+pub mod speaker_activity;
 pub mod speaker_id;
+pub mod speaker_merge;
 pub mod token;
 pub mod window;
 
 pub use deepgram::*;
 pub use patch::*;
+pub use provenance::*;
+pub use speaker_activity::*;
 pub use speaker_id::*;
+pub use speaker_merge::*;
 pub use token::*;
 pub use window::*;
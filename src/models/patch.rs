@@ -16,6 +16,18 @@ pub enum ReasonCode {
     BackchannelAttribution,
     /// Explicitly keeping the token unchanged
     DoNotChange,
+    /// Decided by a human reviewer (never offered to the LLM)
+    HumanReview,
+    /// Speaker label merged into another speaker, e.g. a phantom speaker
+    /// (never offered to the LLM)
+    SpeakerMerge,
+    /// Flipped by Viterbi smoothing with no window voting for the new
+    /// speaker (never offered to the LLM)
+    Smoothing,
+    /// Forced by the minimum turn duration constraint (never offered to the LLM)
+    MinTurnConstraint,
+    /// Forced by the maximum switch rate constraint (never offered to the LLM)
+    SwitchRateConstraint,
 }
 
 /// A single token relabeling operation
@@ -27,6 +39,9 @@ pub struct TokenRelabel {
     pub new_speaker: u32,
     /// Reason for the change
     pub reason: ReasonCode,
+    /// Model-reported confidence in this relabel (0-1), if provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
 }
 
 /// Type of turn edit operation
@@ -85,6 +100,9 @@ pub struct WindowPatch {
     /// Notes and metadata
     #[serde(default)]
     pub notes: PatchNotes,
+    /// Quality signals recorded when the patch passed validation
+    #[serde(skip)]
+    pub quality: WindowQuality,
 }
 
 impl WindowPatch {
@@ -102,6 +120,29 @@ impl WindowPatch {
     pub fn is_empty(&self) -> bool {
         self.token_relabels.is_empty() && self.turn_edits.is_empty()
     }
+
+    /// Check if the LLM flagged a token as uncertain
+    pub fn is_uncertain(&self, token_id: &str) -> bool {
+        self.notes.uncertain_tokens.iter().any(|t| t == token_id)
+    }
+}
+
+/// Quality of a window patch as measured during validation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowQuality {
+    /// Fraction of the edit budget left unused (0.0 - 1.0)
+    pub budget_margin: f64,
+    /// Change in the validation cost function (negative = improvement)
+    pub cost_delta: f64,
+}
+
+impl Default for WindowQuality {
+    fn default() -> Self {
+        Self {
+            budget_margin: 1.0,
+            cost_delta: 0.0,
+        }
+    }
 }
 
 /// Validation result for a patch
@@ -113,14 +154,17 @@ pub struct PatchValidation {
     pub errors: Vec<String>,
     /// Edit budget usage (0.0 - 1.0)
     pub edit_budget_used: f64,
+    /// Window quality signals for Stage 2 vote weighting
+    pub quality: WindowQuality,
 }
 
 impl PatchValidation {
-    pub fn valid(edit_budget_used: f64) -> Self {
+    pub fn valid(edit_budget_used: f64, quality: WindowQuality) -> Self {
         Self {
             is_valid: true,
             errors: vec![],
             edit_budget_used,
+            quality,
         }
     }
 
@@ -129,6 +173,7 @@ impl PatchValidation {
             is_valid: false,
             errors,
             edit_budget_used: 0.0,
+            quality: WindowQuality::default(),
         }
     }
 }
@@ -142,7 +187,7 @@ mod tests {
         let json = r#"{
             "window_id": "w_0123",
             "token_relabels": [
-                {"token_id": "t_1829", "new_speaker": 1, "reason": "lexical_continuity"}
+                {"token_id": "t_1829", "new_speaker": 1, "reason": "lexical_continuity", "confidence": 0.8}
             ],
             "turn_edits": [
                 {"type": "merge_turns", "turn_id": "turn_88", "to_turn_id": "turn_89", "reason": "jitter_short_turn"}
@@ -160,6 +205,8 @@ mod tests {
         assert_eq!(patch.token_relabels.len(), 1);
         assert_eq!(patch.token_relabels[0].new_speaker, 1);
         assert_eq!(patch.token_relabels[0].reason, ReasonCode::LexicalContinuity);
+        assert_eq!(patch.token_relabels[0].confidence, Some(0.8));
+        assert!(patch.is_uncertain("t_1831"));
         assert!(!patch.has_violations());
     }
 }
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use super::ReasonCode;
+
+/// Pipeline stage that changed a token's speaker label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeStage {
+    /// Deterministic pre-LLM heuristics
+    Heuristics,
+    /// Stage 2 reconciliation of LLM window patches
+    Reconciliation,
+    /// Stage 2 post-reconciliation constraints
+    Constraints,
+    /// Human reviewer decision
+    Review,
+    /// Global merge of two speaker IDs across the recording
+    SpeakerMerge,
+}
+
+/// A single speaker label change recorded on a token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelChange {
+    /// Stage that made the change
+    pub stage: ChangeStage,
+    /// Rule name (heuristics, constraints), window ID(s) (reconciliation)
+    /// or review item
+    pub source: String,
+    /// Reason code for the change
+    pub reason: ReasonCode,
+    /// Speaker before the change
+    pub from_speaker: u32,
+    /// Speaker after the change
+    pub to_speaker: u32,
+    /// Vote weight behind the change, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
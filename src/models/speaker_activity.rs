@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use super::TokenizedTranscript;
+
+/// Words, turns and talk time of one speaker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeakerActivity {
+    pub speaker: u32,
+    pub words: usize,
+    pub turns: usize,
+    /// Sum of turn durations
+    pub talk_ms: u64,
+}
+
+impl SpeakerActivity {
+    /// Activity of every speaker with at least one turn, in speaker ID order
+    pub fn from_transcript(transcript: &TokenizedTranscript) -> Vec<Self> {
+        let mut activity: BTreeMap<u32, Self> = BTreeMap::new();
+        for turn in &transcript.turns {
+            let a = activity.entry(turn.speaker).or_insert(Self {
+                speaker: turn.speaker,
+                words: 0,
+                turns: 0,
+                talk_ms: 0,
+            });
+            a.words += turn.token_count();
+            a.turns += 1;
+            a.talk_ms += turn.duration_ms();
+        }
+        activity.into_values().collect()
+    }
+}
+
+/// Thresholds for phantom speakers: labels a diarizer invents for a handful
+/// of words
+#[derive(Debug, Clone)]
+pub struct PhantomSpeakerConfig {
+    /// A speaker with less talk time than this may be a phantom
+    pub max_talk_ms: u64,
+    /// A speaker with at most this many turns may be a phantom
+    pub max_turns: usize,
+    /// Only speakers below this share of all talk time (0.0-1.0) are phantoms
+    pub max_share: f64,
+}
+
+impl Default for PhantomSpeakerConfig {
+    fn default() -> Self {
+        Self {
+            max_talk_ms: 3_000,
+            max_turns: 2,
+            max_share: 0.05,
+        }
+    }
+}
+
+impl PhantomSpeakerConfig {
+    /// Whether a speaker looks like a phantom, given all speakers' talk time
+    pub fn is_phantom(&self, activity: &SpeakerActivity, total_talk_ms: u64) -> bool {
+        let share = activity.talk_ms as f64 / total_talk_ms.max(1) as f64;
+        (activity.talk_ms < self.max_talk_ms || activity.turns <= self.max_turns)
+            && share < self.max_share
+    }
+
+    /// Speakers that look like phantoms; never all of them
+    pub fn phantom_speakers(&self, activity: &[SpeakerActivity]) -> Vec<u32> {
+        let total_talk_ms = activity.iter().map(|a| a.talk_ms).sum();
+        let phantoms: Vec<u32> = activity
+            .iter()
+            .filter(|a| self.is_phantom(a, total_talk_ms))
+            .map(|a| a.speaker)
+            .collect();
+        if phantoms.len() == activity.len() {
+            return vec![];
+        }
+        phantoms
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::llm::Usage;
+
+/// Configuration for the global speaker merge stage
+#[derive(Debug, Clone)]
+pub struct SpeakerMergeConfig {
+    /// Pairs scoring at least this (0.0-1.0) are proposed, and sent to the
+    /// LLM check when it runs
+    pub min_candidate_confidence: f64,
+    /// Proposals at or above this confidence (0.0-1.0) are applied
+    pub min_confidence: f64,
+    /// A speaker change with a shorter gap and no sentence end before it
+    /// counts as a mid-sentence continuation
+    pub continuation_gap_ms: u64,
+    /// Minimum time overlap of two speakers' words that counts as both
+    /// talking at once, which rules out a merge
+    pub min_overlap_ms: u64,
+    /// Maximum number of transcript excerpts per speaker in the LLM check
+    pub max_excerpts_per_speaker: usize,
+}
+
+impl Default for SpeakerMergeConfig {
+    fn default() -> Self {
+        Self {
+            min_candidate_confidence: 0.5,
+            min_confidence: 0.75,
+            continuation_gap_ms: 500,
+            min_overlap_ms: 50,
+            max_excerpts_per_speaker: 4,
+        }
+    }
+}
+
+/// A proposal to merge one speaker ID into another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerMerge {
+    /// Speaker ID that is relabeled
+    pub from_speaker: u32,
+    /// Speaker ID it is merged into
+    pub to_speaker: u32,
+    /// Confidence that both IDs are one person (0.0-1.0)
+    pub confidence: f64,
+    /// Observations behind the confidence
+    pub evidence: Vec<String>,
+    /// LLM verdict, if the proposal was checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_same_person: Option<bool>,
+    /// Whether the merge was applied to the transcript
+    pub applied: bool,
+    /// Tokens relabeled (locked tokens keep their label)
+    pub tokens_relabeled: usize,
+}
+
+/// LLM verdict on one proposed merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerMergeVerdict {
+    pub from_speaker: u32,
+    pub to_speaker: u32,
+    /// Whether both speaker IDs are the same person
+    pub same_person: bool,
+    /// Confidence in the verdict (0.0-1.0)
+    pub confidence: f64,
+    /// Quotes or observations supporting the verdict
+    #[serde(default)]
+    pub evidence: Vec<String>,
+}
+
+/// Result of the speaker merge stage
+#[derive(Debug, Clone, Default)]
+pub struct SpeakerMergeResult {
+    /// Every proposal, applied or not, by descending confidence
+    pub merges: Vec<SpeakerMerge>,
+    /// Final speaker ID for every merged speaker ID
+    pub remapping: BTreeMap<u32, u32>,
+    /// Total tokens relabeled
+    pub tokens_relabeled: usize,
+    /// API token usage of the LLM check
+    pub usage: Usage,
+}
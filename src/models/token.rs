@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::DeepgramWord;
+use super::{ChangeStage, DeepgramWord, LabelChange, ReasonCode};
 
 /// Internal token representation with millisecond timestamps and generated IDs
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,18 +17,42 @@ pub struct Token {
     pub end_ms: u64,
     /// Numeric speaker ID (from Deepgram)
     pub speaker: u32,
-    /// Speaker assignment confidence (0-1)
-    pub speaker_conf: f64,
+    /// Speaker assignment confidence (0-1) from the diarizer; `None` when it
+    /// gives none (always for Deepgram streaming)
+    #[serde(default)]
+    pub speaker_conf: Option<f64>,
+    /// Speaker confidence estimated from turn structure in Stage 0, for
+    /// tokens without `speaker_conf`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_speaker_conf: Option<f64>,
     /// Transcription accuracy confidence (0-1)
     pub transcription_conf: f64,
-    /// Whether this token is in an overlap region
+    /// Whether this token overlaps in time with speech from another speaker
     pub is_overlap_region: bool,
+    /// Whether this token is on either side of a quick speaker change that is
+    /// not overlapped speech
+    #[serde(default)]
+    pub is_tight_handoff: bool,
+    /// Silence between the end of the previous token and the start of this
+    /// one (0 for the first token and when the two overlap)
+    #[serde(default)]
+    pub pause_before_ms: u64,
+    /// Whether the pause before this token makes it a likely turn-taking
+    /// point (set in Stage 0)
+    #[serde(default)]
+    pub is_turn_point: bool,
     /// Segment identifier
     pub segment_id: String,
     /// Turn identifier (changes when speaker changes)
     pub turn_id: String,
     /// Original index in the source transcript
     pub original_index: usize,
+    /// Every speaker label change applied to this token, in order
+    #[serde(default)]
+    pub provenance: Vec<LabelChange>,
+    /// Label confirmed by a human reviewer; no stage may change it
+    #[serde(default)]
+    pub locked: bool,
 }
 
 impl Token {
@@ -41,19 +65,57 @@ impl Token {
             start_ms: (word.start * 1000.0) as u64,
             end_ms: (word.end * 1000.0) as u64,
             speaker: word.speaker,
-            speaker_conf: word.speaker_confidence.unwrap_or(0.5),
+            speaker_conf: word.speaker_confidence,
+            estimated_speaker_conf: None,
             transcription_conf: word.confidence,
             is_overlap_region: false,
+            is_tight_handoff: false,
+            pause_before_ms: 0,
+            is_turn_point: false,
             segment_id: segment_id.to_string(),
             turn_id: turn_id.to_string(),
             original_index: index,
+            provenance: Vec::new(),
+            locked: false,
         }
     }
 
+    /// Change the speaker label, recording the change in the provenance log
+    pub fn relabel(
+        &mut self,
+        new_speaker: u32,
+        stage: ChangeStage,
+        source: impl Into<String>,
+        reason: ReasonCode,
+        weight: Option<f64>,
+    ) {
+        self.provenance.push(LabelChange {
+            stage,
+            source: source.into(),
+            reason,
+            from_speaker: self.speaker,
+            to_speaker: new_speaker,
+            weight,
+        });
+        self.speaker = new_speaker;
+    }
+
     /// Duration of this token in milliseconds
     pub fn duration_ms(&self) -> u64 {
         self.end_ms.saturating_sub(self.start_ms)
     }
+
+    /// Speaker confidence from the diarizer, else the Stage 0 estimate;
+    /// `None` when neither is known
+    pub fn speaker_confidence(&self) -> Option<f64> {
+        self.speaker_conf.or(self.estimated_speaker_conf)
+    }
+
+    /// Whether `speaker_confidence` is an estimate rather than the
+    /// diarizer's own score
+    pub fn is_speaker_conf_estimated(&self) -> bool {
+        self.speaker_conf.is_none() && self.estimated_speaker_conf.is_some()
+    }
 }
 
 /// A turn is a contiguous sequence of tokens from the same speaker
@@ -69,6 +131,9 @@ pub struct Turn {
     pub end_ms: u64,
     /// Indices into the token array
     pub token_indices: Vec<usize>,
+    /// Silence before the first token of this turn
+    #[serde(default)]
+    pub pause_before_ms: u64,
 }
 
 impl Turn {
@@ -110,6 +175,18 @@ impl TokenizedTranscript {
         self.turns.iter().find(|t| t.turn_id == turn_id)
     }
 
+    /// Longest pause on either side of the tokens `first..=last`
+    pub fn pause_around_ms(&self, first: usize, last: usize) -> u64 {
+        let before = self.tokens.get(first).map_or(0, |t| t.pause_before_ms);
+        let after = self.tokens.get(last + 1).map_or(0, |t| t.pause_before_ms);
+        before.max(after)
+    }
+
+    /// End of the last token in milliseconds
+    pub fn end_ms(&self) -> u64 {
+        self.tokens.last().map(|t| t.end_ms).unwrap_or(0)
+    }
+
     /// Total duration in milliseconds
     pub fn duration_ms(&self) -> u64 {
         self.tokens
@@ -143,6 +220,38 @@ mod tests {
         assert_eq!(token.end_ms, 800);
         assert_eq!(token.duration_ms(), 300);
         assert_eq!(token.speaker, 0);
-        assert_eq!(token.speaker_conf, 0.85);
+        assert_eq!(token.speaker_conf, Some(0.85));
+        assert_eq!(token.speaker_confidence(), Some(0.85));
+        assert!(!token.is_speaker_conf_estimated());
+    }
+
+    #[test]
+    fn test_relabel_records_provenance() {
+        let dg_word = DeepgramWord {
+            word: "yeah".to_string(),
+            start: 1.0,
+            end: 1.2,
+            confidence: 0.9,
+            speaker: 0,
+            speaker_confidence: Some(0.4),
+            punctuated_word: None,
+        };
+        let mut token = Token::from_deepgram(&dg_word, 0, "seg_0", "turn_0");
+
+        token.relabel(
+            1,
+            ChangeStage::Heuristics,
+            "apply_backchannel_rules",
+            ReasonCode::BackchannelAttribution,
+            None,
+        );
+        token.relabel(0, ChangeStage::Reconciliation, "w_3", ReasonCode::DialoguePairing, Some(0.7));
+
+        assert_eq!(token.speaker, 0);
+        assert_eq!(token.provenance.len(), 2);
+        assert_eq!(token.provenance[0].from_speaker, 0);
+        assert_eq!(token.provenance[0].to_speaker, 1);
+        assert_eq!(token.provenance[1].source, "w_3");
+        assert_eq!(token.provenance[1].weight, Some(0.7));
     }
 }
@@ -1,5 +1,9 @@
+use std::str::FromStr;
+
+use anyhow::bail;
 use serde::{Deserialize, Serialize};
 
+use super::PhantomSpeakerConfig;
 
 /// Configuration for window generation
 #[derive(Debug, Clone)]
@@ -12,6 +16,30 @@ pub struct WindowConfig {
     pub anchor_size_ms: u64,
     /// Only process windows intersecting problem zones
     pub filter_problem_zones: bool,
+    /// How windows are placed
+    pub strategy: WindowStrategy,
+    /// Adaptive: problem zones closer than this are covered by one window
+    pub cluster_gap_ms: u64,
+    /// Adaptive: context added before and after each cluster of zones
+    pub context_ms: u64,
+    /// A gap between words at least this long counts as a pause (a place
+    /// where a window may start or end)
+    pub min_pause_ms: u64,
+    /// Sliding: move each window edge to the longest pause or turn change
+    /// within this distance (0 to keep fixed edges)
+    pub pause_snap_ms: u64,
+    /// Split windows with more editable tokens than this
+    pub max_window_tokens: Option<usize>,
+    /// Merge windows with fewer editable tokens than this into the window
+    /// they touch, if the result stays within the other limits
+    pub min_window_tokens: Option<usize>,
+    /// Split windows whose estimated Stage 1 user prompt (editable tokens,
+    /// anchors and hints) is larger than this many tokens
+    pub max_prompt_tokens: Option<u32>,
+    /// Extend anchors beyond `anchor_size_ms` to at least this many tokens
+    pub min_anchor_tokens: Option<usize>,
+    /// Cut anchors within `anchor_size_ms` to at most this many tokens
+    pub max_anchor_tokens: Option<usize>,
 }
 
 impl Default for WindowConfig {
@@ -21,6 +49,39 @@ impl Default for WindowConfig {
             stride_ms: 15_000,        // 15 seconds
             anchor_size_ms: 5_000,    // 5 seconds
             filter_problem_zones: true,
+            strategy: WindowStrategy::Sliding,
+            cluster_gap_ms: 10_000,
+            context_ms: 10_000,
+            min_pause_ms: 500,
+            pause_snap_ms: 2_000,
+            max_window_tokens: None,
+            min_window_tokens: None,
+            max_prompt_tokens: None,
+            min_anchor_tokens: None,
+            max_anchor_tokens: None,
+        }
+    }
+}
+
+/// How processing windows are placed over the transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowStrategy {
+    /// Fixed-size windows every `stride_ms`, filtered to problem zones
+    #[default]
+    Sliding,
+    /// One window per cluster of nearby problem zones, edges snapped to turn
+    /// boundaries or pauses
+    Adaptive,
+}
+
+impl FromStr for WindowStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sliding" => Ok(Self::Sliding),
+            "adaptive" => Ok(Self::Adaptive),
+            _ => bail!("Unknown window strategy {:?} (expected sliding or adaptive)", s),
         }
     }
 }
@@ -44,6 +105,10 @@ pub struct Window {
     pub is_problem_zone: bool,
     /// Problem zone types detected in this window
     pub problem_types: Vec<ProblemType>,
+    /// How badly the window needs fixing (0.0-1.0), from its problem types,
+    /// problem zone density, speaker switch rate and speaker confidence
+    #[serde(default)]
+    pub severity: f64,
 }
 
 impl Window {
@@ -89,8 +154,47 @@ pub enum ProblemType {
     ShortTurn,
     /// Within ±2s of overlap region
     OverlapAdjacent,
+    /// Within ±1s of a tight handoff (quick speaker change without overlap)
+    HandoffAdjacent,
     /// Low speaker confidence
     LowConfidence,
+    /// Turn of a speaker with very little talk time or very few turns
+    PhantomSpeaker,
+    /// Low ASR word confidence, likely crosstalk or mumbling
+    LowTranscriptionConfidence,
+}
+
+impl ProblemType {
+    /// Every problem type
+    pub const ALL: [ProblemType; 7] = [
+        Self::SpeakerJitter,
+        Self::ShortTurn,
+        Self::OverlapAdjacent,
+        Self::HandoffAdjacent,
+        Self::LowConfidence,
+        Self::PhantomSpeaker,
+        Self::LowTranscriptionConfidence,
+    ];
+
+    /// Summed severity weight of the given types relative to all types
+    /// (0.0-1.0, assuming no duplicates)
+    pub fn combined_weight(types: &[ProblemType]) -> f64 {
+        let total: f64 = Self::ALL.iter().map(Self::severity_weight).sum();
+        (types.iter().map(Self::severity_weight).sum::<f64>() / total).min(1.0)
+    }
+
+    /// Contribution of this problem type to window severity
+    pub fn severity_weight(&self) -> f64 {
+        match self {
+            Self::SpeakerJitter => 1.0,
+            Self::PhantomSpeaker => 0.9,
+            Self::OverlapAdjacent => 0.8,
+            Self::LowConfidence => 0.7,
+            Self::ShortTurn => 0.6,
+            Self::LowTranscriptionConfidence => 0.5,
+            Self::HandoffAdjacent => 0.4,
+        }
+    }
 }
 
 /// Configuration for problem zone detection
@@ -102,8 +206,30 @@ pub struct ProblemZoneConfig {
     pub min_turn_duration_ms: u64,
     /// Proximity to overlap in ms to flag as overlap-adjacent
     pub overlap_proximity_ms: u64,
+    /// Tokens from different speakers must intersect by more than this many
+    /// ms to count as overlapped speech
+    pub min_overlap_ms: u64,
+    /// A speaker change with at most this gap (and no overlap) is a tight
+    /// handoff
+    pub handoff_gap_ms: u64,
+    /// Proximity to a tight handoff in ms to flag as handoff-adjacent
+    pub handoff_proximity_ms: u64,
     /// Minimum speaker confidence threshold
     pub min_speaker_confidence: f64,
+    /// Words with a lower ASR confidence count as unreliable
+    pub min_transcription_confidence: f64,
+    /// Fewest consecutive unreliable words that form a low transcription
+    /// confidence zone
+    pub min_low_transcription_tokens: usize,
+    /// Gap between words in ms counted as a pause
+    pub pause_ms: u64,
+    /// Pause in ms after which a new turn is likely, even without a speaker
+    /// change (a pause of `pause_ms` at a speaker change is enough)
+    pub turn_pause_ms: u64,
+    /// Thresholds for flagging the turns of phantom speakers
+    pub phantom: PhantomSpeakerConfig,
+    /// Speaker confidence estimate for tokens the diarizer gave none
+    pub confidence_estimate: SpeakerConfEstimateConfig,
 }
 
 impl Default for ProblemZoneConfig {
@@ -112,7 +238,58 @@ impl Default for ProblemZoneConfig {
             max_switches_per_10s: 3,
             min_turn_duration_ms: 800,
             overlap_proximity_ms: 2_000,
+            min_overlap_ms: 50,
+            handoff_gap_ms: 100,
+            handoff_proximity_ms: 1_000,
             min_speaker_confidence: 0.6,
+            min_transcription_confidence: 0.5,
+            min_low_transcription_tokens: 2,
+            pause_ms: 500,
+            turn_pause_ms: 1_000,
+            phantom: PhantomSpeakerConfig::default(),
+            confidence_estimate: SpeakerConfEstimateConfig::default(),
+        }
+    }
+}
+
+/// Speaker confidence estimate from turn structure, used when the diarizer
+/// gives no speaker confidence
+///
+/// Turn length, position in the turn and distance to a speaker change each
+/// score 0.0-1.0 and are combined (0.4, 0.3, 0.3) into a value between
+/// `min_estimate` and `max_estimate`, which overlap and tight handoffs scale
+/// down.
+#[derive(Debug, Clone)]
+pub struct SpeakerConfEstimateConfig {
+    /// Turns at least this long score fully on turn length
+    pub stable_turn_ms: u64,
+    /// Tokens at least this many words from a speaker change score fully on
+    /// position in the turn
+    pub stable_edge_tokens: usize,
+    /// Tokens at least this many ms from a speaker change score fully on
+    /// distance to a switch
+    pub stable_switch_distance_ms: u64,
+    /// Estimate for a token scoring zero on every signal
+    pub min_estimate: f64,
+    /// Estimate for a token scoring fully on every signal; below 1.0 so an
+    /// estimate never claims certainty
+    pub max_estimate: f64,
+    /// Multiplier for tokens in overlapped speech
+    pub overlap_factor: f64,
+    /// Multiplier for tokens at a tight handoff
+    pub handoff_factor: f64,
+}
+
+impl Default for SpeakerConfEstimateConfig {
+    fn default() -> Self {
+        Self {
+            stable_turn_ms: 5_000,
+            stable_edge_tokens: 3,
+            stable_switch_distance_ms: 2_000,
+            min_estimate: 0.3,
+            max_estimate: 0.95,
+            overlap_factor: 0.5,
+            handoff_factor: 0.8,
         }
     }
 }
@@ -143,6 +320,26 @@ impl WindowSet {
     pub fn problem_window_count(&self) -> usize {
         self.problem_window_indices.len()
     }
+
+    /// Problem windows, most severe first
+    ///
+    /// Keeps only windows with severity of at least `min_severity` and at
+    /// most the `max_windows` most severe.
+    pub fn ranked_problem_windows(
+        &self,
+        max_windows: Option<usize>,
+        min_severity: Option<f64>,
+    ) -> Vec<&Window> {
+        let mut windows: Vec<&Window> = self
+            .problem_windows()
+            .filter(|w| min_severity.is_none_or(|min| w.severity >= min))
+            .collect();
+        windows.sort_by(|a, b| b.severity.total_cmp(&a.severity));
+        if let Some(max) = max_windows {
+            windows.truncate(max);
+        }
+        windows
+    }
 }
 
 #[cfg(test)]
@@ -160,6 +357,7 @@ mod tests {
             anchor_suffix_indices: vec![],
             is_problem_zone: true,
             problem_types: vec![],
+            severity: 0.0,
         };
 
         assert!((window.proximity_to_center(5_000) - 1.0).abs() < 0.001);
@@ -167,4 +365,30 @@ mod tests {
         assert!((window.proximity_to_center(10_000) - 0.0).abs() < 0.001);
         assert!((window.proximity_to_center(2_500) - 0.5).abs() < 0.001);
     }
+
+    #[test]
+    fn test_ranked_problem_windows() {
+        let window = |id: usize, severity: f64| Window {
+            window_id: format!("w_{}", id),
+            start_ms: 0,
+            end_ms: 10_000,
+            token_indices: vec![],
+            anchor_prefix_indices: vec![],
+            anchor_suffix_indices: vec![],
+            is_problem_zone: true,
+            problem_types: vec![],
+            severity,
+        };
+        let set = WindowSet {
+            windows: vec![window(0, 0.2), window(1, 0.9), window(2, 0.5), window(3, 0.7)],
+            problem_window_indices: vec![0, 1, 2],
+        };
+
+        let ids = |windows: Vec<&Window>| -> Vec<String> {
+            windows.iter().map(|w| w.window_id.clone()).collect()
+        };
+        assert_eq!(ids(set.ranked_problem_windows(None, None)), ["w_1", "w_2", "w_0"]);
+        assert_eq!(ids(set.ranked_problem_windows(Some(1), None)), ["w_1"]);
+        assert_eq!(ids(set.ranked_problem_windows(None, Some(0.4))), ["w_1", "w_2"]);
+    }
 }
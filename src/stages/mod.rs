@@ -1,11 +1,15 @@
 pub mod stage0_normalize;
 pub mod stage1_llm_edit;
 pub mod stage2_reconcile;
+pub mod stage2_smoothing;
 pub mod stage3_render;
 pub mod stage_speaker_id;
+pub mod stage_speaker_merge;
 
 pub use stage0_normalize::*;
 pub use stage1_llm_edit::*;
 pub use stage2_reconcile::*;
+pub use stage2_smoothing::*;
 pub use stage3_render::*;
 pub use stage_speaker_id::*;
+pub use stage_speaker_merge::*;
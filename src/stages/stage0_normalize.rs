@@ -1,6 +1,11 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::llm::{build_window_prompt, estimate_tokens};
 use crate::models::{
-    ProblemType, ProblemZoneConfig, TokenizedTranscript, Window, WindowConfig,
-    WindowSet,
+    ProblemType, ProblemZoneConfig, SpeakerActivity, SpeakerConfEstimateConfig, Token,
+    TokenizedTranscript, Window, WindowConfig, WindowSet, WindowStrategy,
 };
 
 /// Result of Stage 0 normalization
@@ -12,10 +17,52 @@ pub struct NormalizationResult {
     pub windows: WindowSet,
     /// Detected problem zones
     pub problem_zones: Vec<ProblemZone>,
+    /// Problem zones unioned into non-overlapping regions, used for windows
+    pub merged_zones: Vec<MergedZone>,
+    /// Pauses of at least `ProblemZoneConfig::pause_ms`, in order
+    pub pauses: Vec<Pause>,
+}
+
+/// Overlapping problem zones combined into one region
+#[derive(Debug, Clone, Serialize)]
+pub struct MergedZone {
+    /// Start time in milliseconds
+    pub start_ms: u64,
+    /// End time in milliseconds
+    pub end_ms: u64,
+    /// Every problem type of the zones merged into this region
+    pub problem_types: Vec<ProblemType>,
+    /// Affected token indices, sorted and de-duplicated
+    pub token_indices: Vec<usize>,
+    /// Number of detected zones merged into this region
+    pub zone_count: usize,
+    /// Combined weight of the problem types present (0.0-1.0)
+    pub score: f64,
+}
+
+/// A silence between two consecutive tokens
+#[derive(Debug, Clone, Serialize)]
+pub struct Pause {
+    /// Index of the token after the pause
+    pub token_index: usize,
+    /// End of the token before the pause
+    pub start_ms: u64,
+    /// Start of the token after the pause
+    pub end_ms: u64,
+    /// Whether the speaker label changes across the pause
+    pub speaker_change: bool,
+    /// Whether the pause is a likely turn-taking point
+    pub is_turn_point: bool,
+}
+
+impl Pause {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms - self.start_ms
+    }
 }
 
 /// A detected problem zone in the transcript
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProblemZone {
     /// Start time in milliseconds
     pub start_ms: u64,
@@ -30,8 +77,10 @@ pub struct ProblemZone {
 /// Perform Stage 0: Pre-LLM normalization
 ///
 /// This stage:
-/// 1. Detects problem zones (jitter, short turns, overlap, low confidence)
-/// 2. Marks overlap regions on tokens
+/// 1. Detects problem zones (jitter, short turns, overlap, low confidence,
+///    phantom speakers)
+/// 2. Marks overlapped speech, tight handoffs and likely turn-taking points
+///    on tokens
 /// 3. Builds processing windows
 /// 4. Filters windows to only those intersecting problem zones
 pub fn normalize(
@@ -39,42 +88,189 @@ pub fn normalize(
     window_config: &WindowConfig,
     problem_config: &ProblemZoneConfig,
 ) -> NormalizationResult {
-    // Detect overlap regions
-    detect_overlap_regions(transcript);
+    // Detect overlapped speech and tight handoffs
+    detect_overlap_regions(transcript, problem_config);
+
+    // Detect pauses and mark likely turn-taking points
+    let pauses = detect_pauses(transcript, problem_config);
+
+    // Estimate speaker confidence where the diarizer gave none
+    estimate_speaker_confidence(transcript, &problem_config.confidence_estimate);
 
     // Detect all problem zones
     let problem_zones = detect_problem_zones(transcript, problem_config);
 
-    // Build windows
-    let windows = build_windows(transcript, window_config, &problem_zones);
+    // Merge overlapping zones and build windows around them
+    let merged_zones = merge_problem_zones(&problem_zones);
+    let windows = build_windows(transcript, window_config, &merged_zones);
 
     NormalizationResult {
         transcript: transcript.clone(),
         windows,
         problem_zones,
+        merged_zones,
+        pauses,
     }
 }
 
-/// Detect overlap regions where multiple speakers might be active
-fn detect_overlap_regions(transcript: &mut TokenizedTranscript) {
-    // Simple heuristic: if two consecutive tokens have different speakers
-    // and their time ranges overlap or are very close, mark as overlap region
-    let overlap_threshold_ms = 100; // 100ms gap or less
+/// Union problem zones whose time ranges overlap or touch, whatever their
+/// type, into consolidated regions
+pub fn merge_problem_zones(zones: &[ProblemZone]) -> Vec<MergedZone> {
+    let mut sorted: Vec<&ProblemZone> = zones.iter().collect();
+    sorted.sort_by_key(|z| (z.start_ms, z.end_ms));
 
-    for i in 0..transcript.tokens.len() {
-        if i + 1 < transcript.tokens.len() {
-            let current = &transcript.tokens[i];
-            let next = &transcript.tokens[i + 1];
-
-            // Check if speakers differ and times are close/overlapping
-            if current.speaker != next.speaker {
-                let gap = next.start_ms.saturating_sub(current.end_ms);
-                if gap <= overlap_threshold_ms || next.start_ms < current.end_ms {
-                    // Mark both tokens as overlap region
-                    transcript.tokens[i].is_overlap_region = true;
-                    transcript.tokens[i + 1].is_overlap_region = true;
+    let mut merged: Vec<MergedZone> = Vec::new();
+    for zone in sorted {
+        match merged.last_mut() {
+            Some(region) if zone.start_ms <= region.end_ms => {
+                region.end_ms = region.end_ms.max(zone.end_ms);
+                if !region.problem_types.contains(&zone.problem_type) {
+                    region.problem_types.push(zone.problem_type);
                 }
+                region.token_indices.extend(&zone.token_indices);
+                region.zone_count += 1;
             }
+            _ => merged.push(MergedZone {
+                start_ms: zone.start_ms,
+                end_ms: zone.end_ms,
+                problem_types: vec![zone.problem_type],
+                token_indices: zone.token_indices.clone(),
+                zone_count: 1,
+                score: 0.0,
+            }),
+        }
+    }
+
+    for region in &mut merged {
+        region.token_indices.sort_unstable();
+        region.token_indices.dedup();
+        region.score = ProblemType::combined_weight(&region.problem_types);
+    }
+    merged
+}
+
+/// Find pauses between tokens and mark likely turn-taking points
+///
+/// A token is a turn point if it follows a pause of `turn_pause_ms`, or a
+/// pause of `pause_ms` where the speaker label changes.
+fn detect_pauses(transcript: &mut TokenizedTranscript, config: &ProblemZoneConfig) -> Vec<Pause> {
+    let mut pauses = Vec::new();
+
+    for i in 1..transcript.tokens.len() {
+        let gap = transcript.tokens[i].pause_before_ms;
+        let speaker_change = transcript.tokens[i - 1].speaker != transcript.tokens[i].speaker;
+        let is_turn_point =
+            gap >= config.turn_pause_ms || (speaker_change && gap >= config.pause_ms);
+        transcript.tokens[i].is_turn_point = is_turn_point;
+
+        if gap >= config.pause_ms {
+            pauses.push(Pause {
+                token_index: i,
+                start_ms: transcript.tokens[i].start_ms - gap,
+                end_ms: transcript.tokens[i].start_ms,
+                speaker_change,
+                is_turn_point,
+            });
+        }
+    }
+
+    pauses
+}
+
+/// Mark overlapped speech and tight handoffs on tokens
+///
+/// Tokens from different speakers whose time ranges intersect by more than
+/// `min_overlap_ms` are overlapped speech. Tokens are compared in time order
+/// rather than transcript order, so interleaved input (e.g. one stream per
+/// channel) is handled. A speaker change between tokens that follow each
+/// other in time with a gap of at most `handoff_gap_ms` is a tight handoff,
+/// unless both tokens are overlapped speech.
+fn detect_overlap_regions(transcript: &mut TokenizedTranscript, config: &ProblemZoneConfig) {
+    let tokens = &mut transcript.tokens;
+    for token in tokens.iter_mut() {
+        token.is_overlap_region = false;
+        token.is_tight_handoff = false;
+    }
+
+    let mut order: Vec<usize> = (0..tokens.len()).collect();
+    order.sort_by_key(|&i| tokens[i].start_ms);
+
+    for (pos, &i) in order.iter().enumerate() {
+        for &j in &order[pos + 1..] {
+            if tokens[j].start_ms >= tokens[i].end_ms {
+                break;
+            }
+            let overlap_ms =
+                tokens[i].end_ms.min(tokens[j].end_ms).saturating_sub(tokens[j].start_ms);
+            if tokens[i].speaker != tokens[j].speaker && overlap_ms > config.min_overlap_ms {
+                tokens[i].is_overlap_region = true;
+                tokens[j].is_overlap_region = true;
+            }
+        }
+    }
+
+    for pair in order.windows(2) {
+        let (prev, next) = (pair[0], pair[1]);
+        let gap_ms = tokens[next].start_ms.saturating_sub(tokens[prev].end_ms);
+        if tokens[prev].speaker == tokens[next].speaker
+            || gap_ms > config.handoff_gap_ms
+            || (tokens[prev].is_overlap_region && tokens[next].is_overlap_region)
+        {
+            continue;
+        }
+        tokens[prev].is_tight_handoff |= !tokens[prev].is_overlap_region;
+        tokens[next].is_tight_handoff |= !tokens[next].is_overlap_region;
+    }
+}
+
+/// Fill `estimated_speaker_conf` for tokens without a diarizer confidence
+///
+/// Long turns, words deep inside a turn and words far from a speaker change
+/// are rarely mislabeled; overlapped speech and tight handoffs often are.
+/// Turn edges at the start and end of the transcript are not speaker
+/// changes and don't count.
+fn estimate_speaker_confidence(
+    transcript: &mut TokenizedTranscript,
+    config: &SpeakerConfEstimateConfig,
+) {
+    let last_turn = transcript.turns.len().saturating_sub(1);
+    for (t, turn) in transcript.turns.iter().enumerate() {
+        let turn_score =
+            (turn.duration_ms() as f64 / config.stable_turn_ms.max(1) as f64).min(1.0);
+        let n = turn.token_indices.len();
+
+        for (k, &i) in turn.token_indices.iter().enumerate() {
+            let token = &transcript.tokens[i];
+            if token.speaker_conf.is_some() {
+                continue;
+            }
+
+            // Words and ms to the nearest speaker change around this turn
+            let mut edge_tokens = usize::MAX;
+            let mut switch_ms = u64::MAX;
+            if t > 0 {
+                edge_tokens = k;
+                switch_ms = token.start_ms.saturating_sub(turn.start_ms);
+            }
+            if t < last_turn {
+                edge_tokens = edge_tokens.min(n - 1 - k);
+                switch_ms = switch_ms.min(turn.end_ms.saturating_sub(token.end_ms));
+            }
+            let position_score =
+                (edge_tokens as f64 / config.stable_edge_tokens.max(1) as f64).min(1.0);
+            let switch_score =
+                (switch_ms as f64 / config.stable_switch_distance_ms.max(1) as f64).min(1.0);
+
+            let score = 0.4 * turn_score + 0.3 * position_score + 0.3 * switch_score;
+            let mut estimate =
+                config.min_estimate + (config.max_estimate - config.min_estimate) * score;
+            if token.is_overlap_region {
+                estimate *= config.overlap_factor;
+            } else if token.is_tight_handoff {
+                estimate *= config.handoff_factor;
+            }
+
+            transcript.tokens[i].estimated_speaker_conf = Some(estimate.clamp(0.0, 1.0));
         }
     }
 }
@@ -92,12 +288,19 @@ fn detect_problem_zones(
     // 2. Detect short turns
     zones.extend(detect_short_turns(transcript, config));
 
-    // 3. Detect overlap-adjacent regions
+    // 3. Detect regions adjacent to overlapped speech and tight handoffs
     zones.extend(detect_overlap_adjacent(transcript, config));
+    zones.extend(detect_handoff_adjacent(transcript, config));
 
     // 4. Detect low confidence regions
     zones.extend(detect_low_confidence(transcript, config));
 
+    // 5. Detect turns of phantom speakers
+    zones.extend(detect_phantom_speakers(transcript, config));
+
+    // 6. Detect runs of low ASR confidence
+    zones.extend(detect_low_transcription_confidence(transcript, config));
+
     zones
 }
 
@@ -183,8 +386,6 @@ fn detect_overlap_adjacent(
     transcript: &TokenizedTranscript,
     config: &ProblemZoneConfig,
 ) -> Vec<ProblemZone> {
-    let mut zones = Vec::new();
-
     // Find all overlap region tokens
     let overlap_times: Vec<(u64, u64)> = transcript
         .tokens
@@ -193,75 +394,153 @@ fn detect_overlap_adjacent(
         .map(|t| (t.start_ms, t.end_ms))
         .collect();
 
-    if overlap_times.is_empty() {
-        return zones;
-    }
-
-    // Find tokens within proximity of overlap regions
-    for (overlap_start, overlap_end) in &overlap_times {
-        let zone_start = overlap_start.saturating_sub(config.overlap_proximity_ms);
-        let zone_end = overlap_end + config.overlap_proximity_ms;
+    overlap_times
+        .iter()
+        .filter_map(|&(start_ms, end_ms)| {
+            adjacent_zone(
+                transcript,
+                start_ms.saturating_sub(config.overlap_proximity_ms),
+                end_ms + config.overlap_proximity_ms,
+                ProblemType::OverlapAdjacent,
+                |t| !t.is_overlap_region,
+            )
+        })
+        .collect()
+}
 
-        let affected: Vec<usize> = transcript
-            .tokens
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| {
-                !t.is_overlap_region && t.end_ms >= zone_start && t.start_ms <= zone_end
-            })
-            .map(|(i, _)| i)
-            .collect();
+/// Detect regions adjacent to tight handoffs
+///
+/// Mono input rarely has overlapped speech, so quick speaker changes are
+/// the main sign of a mislabeled boundary there. These zones weigh less
+/// than overlap-adjacent ones.
+fn detect_handoff_adjacent(
+    transcript: &TokenizedTranscript,
+    config: &ProblemZoneConfig,
+) -> Vec<ProblemZone> {
+    transcript
+        .tokens
+        .windows(2)
+        .filter(|pair| {
+            pair[0].speaker != pair[1].speaker
+                && pair[0].is_tight_handoff
+                && pair[1].is_tight_handoff
+        })
+        .filter_map(|pair| {
+            adjacent_zone(
+                transcript,
+                pair[0].start_ms.saturating_sub(config.handoff_proximity_ms),
+                pair[1].end_ms + config.handoff_proximity_ms,
+                ProblemType::HandoffAdjacent,
+                |_| true,
+            )
+        })
+        .collect()
+}
 
-        if !affected.is_empty() {
-            zones.push(ProblemZone {
-                start_ms: zone_start,
-                end_ms: zone_end,
-                problem_type: ProblemType::OverlapAdjacent,
-                token_indices: affected,
-            });
-        }
-    }
+/// Zone over the tokens in a time range that match `include`, if any do
+fn adjacent_zone(
+    transcript: &TokenizedTranscript,
+    zone_start: u64,
+    zone_end: u64,
+    problem_type: ProblemType,
+    include: impl Fn(&Token) -> bool,
+) -> Option<ProblemZone> {
+    let affected: Vec<usize> = transcript
+        .tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| include(t) && t.end_ms >= zone_start && t.start_ms <= zone_end)
+        .map(|(i, _)| i)
+        .collect();
 
-    zones
+    (!affected.is_empty()).then_some(ProblemZone {
+        start_ms: zone_start,
+        end_ms: zone_end,
+        problem_type,
+        token_indices: affected,
+    })
 }
 
-/// Detect regions with low speaker confidence
+/// Detect regions with low speaker confidence (diarizer or estimated);
+/// tokens of unknown confidence are not flagged
 fn detect_low_confidence(
     transcript: &TokenizedTranscript,
     config: &ProblemZoneConfig,
+) -> Vec<ProblemZone> {
+    detect_token_runs(transcript, ProblemType::LowConfidence, 1, |token| {
+        token
+            .speaker_confidence()
+            .is_some_and(|c| c < config.min_speaker_confidence)
+    })
+}
+
+/// Flag every turn of a speaker with very little talk time or very few turns
+fn detect_phantom_speakers(
+    transcript: &TokenizedTranscript,
+    config: &ProblemZoneConfig,
+) -> Vec<ProblemZone> {
+    let phantoms = config
+        .phantom
+        .phantom_speakers(&SpeakerActivity::from_transcript(transcript));
+
+    transcript
+        .turns
+        .iter()
+        .filter(|turn| phantoms.contains(&turn.speaker))
+        .map(|turn| ProblemZone {
+            start_ms: turn.start_ms,
+            end_ms: turn.end_ms.max(turn.start_ms + 1),
+            problem_type: ProblemType::PhantomSpeaker,
+            token_indices: turn.token_indices.clone(),
+        })
+        .collect()
+}
+
+/// Detect runs of words the ASR was unsure of, likely crosstalk or mumbling
+///
+/// Single unsure words are common in clean speech, so a run needs
+/// `min_low_transcription_tokens` consecutive words.
+fn detect_low_transcription_confidence(
+    transcript: &TokenizedTranscript,
+    config: &ProblemZoneConfig,
+) -> Vec<ProblemZone> {
+    detect_token_runs(
+        transcript,
+        ProblemType::LowTranscriptionConfidence,
+        config.min_low_transcription_tokens,
+        |token| token.transcription_conf < config.min_transcription_confidence,
+    )
+}
+
+/// One zone per run of at least `min_tokens` consecutive matching tokens
+fn detect_token_runs(
+    transcript: &TokenizedTranscript,
+    problem_type: ProblemType,
+    min_tokens: usize,
+    matches: impl Fn(&Token) -> bool,
 ) -> Vec<ProblemZone> {
     let mut zones = Vec::new();
     let mut current_zone_tokens: Vec<usize> = Vec::new();
 
-    for (i, token) in transcript.tokens.iter().enumerate() {
-        if token.speaker_conf < config.min_speaker_confidence {
+    // A trailing non-matching step closes the last run
+    for i in 0..=transcript.tokens.len() {
+        if transcript.tokens.get(i).is_some_and(&matches) {
             current_zone_tokens.push(i);
         } else if !current_zone_tokens.is_empty() {
-            // Close current zone
-            let first = &transcript.tokens[current_zone_tokens[0]];
-            let last = &transcript.tokens[*current_zone_tokens.last().unwrap()];
-            zones.push(ProblemZone {
-                start_ms: first.start_ms,
-                end_ms: last.end_ms,
-                problem_type: ProblemType::LowConfidence,
-                token_indices: current_zone_tokens.clone(),
-            });
+            if current_zone_tokens.len() >= min_tokens {
+                let first = &transcript.tokens[current_zone_tokens[0]];
+                let last = &transcript.tokens[*current_zone_tokens.last().unwrap()];
+                zones.push(ProblemZone {
+                    start_ms: first.start_ms,
+                    end_ms: last.end_ms,
+                    problem_type,
+                    token_indices: current_zone_tokens.clone(),
+                });
+            }
             current_zone_tokens.clear();
         }
     }
 
-    // Don't forget the last zone
-    if !current_zone_tokens.is_empty() {
-        let first = &transcript.tokens[current_zone_tokens[0]];
-        let last = &transcript.tokens[*current_zone_tokens.last().unwrap()];
-        zones.push(ProblemZone {
-            start_ms: first.start_ms,
-            end_ms: last.end_ms,
-            problem_type: ProblemType::LowConfidence,
-            token_indices: current_zone_tokens,
-        });
-    }
-
     zones
 }
 
@@ -269,106 +548,446 @@ fn detect_low_confidence(
 fn build_windows(
     transcript: &TokenizedTranscript,
     config: &WindowConfig,
-    problem_zones: &[ProblemZone],
+    problem_zones: &[MergedZone],
 ) -> WindowSet {
-    let mut windows = Vec::new();
-
     if transcript.tokens.is_empty() {
         return WindowSet {
-            windows,
+            windows: vec![],
             problem_window_indices: vec![],
         };
     }
 
-    let total_duration = transcript.duration_ms();
-    let start_offset = transcript.tokens[0].start_ms;
-    let mut window_start = start_offset;
-    let mut window_id = 0u64;
+    let zone_tokens: HashSet<usize> = problem_zones
+        .iter()
+        .flat_map(|z| z.token_indices.iter().copied())
+        .collect();
+    let builder = SpanBuilder {
+        transcript,
+        config,
+        problem_zones,
+        zone_tokens: &zone_tokens,
+    };
+
+    let spans = match config.strategy {
+        WindowStrategy::Sliding => sliding_spans(transcript, config),
+        WindowStrategy::Adaptive => adaptive_spans(transcript, config, problem_zones),
+    };
+    let spans = builder.fit_to_limits(spans);
 
-    while window_start < start_offset + total_duration {
+    let windows: Vec<Window> = spans
+        .into_iter()
+        .enumerate()
+        .map(|(id, span)| builder.window(id, span))
+        .collect();
+
+    // Identify problem windows
+    let problem_window_indices = windows
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| w.is_problem_zone || !config.filter_problem_zones)
+        .map(|(i, _)| i)
+        .collect();
+
+    WindowSet {
+        windows,
+        problem_window_indices,
+    }
+}
+
+/// Edit budget used when estimating window prompt sizes (it only changes a
+/// few digits of the prompt header)
+const ESTIMATE_EDIT_BUDGET_PERCENT: f64 = 3.0;
+
+/// A window before anchors and scores are attached: tokens `[start, end)`
+/// covering `[start_ms, end_ms)`
+#[derive(Debug, Clone, Copy)]
+struct WindowSpan {
+    start: usize,
+    end: usize,
+    start_ms: u64,
+    end_ms: u64,
+}
+
+impl WindowSpan {
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Fixed-size windows every `stride_ms`
+fn sliding_spans(transcript: &TokenizedTranscript, config: &WindowConfig) -> Vec<WindowSpan> {
+    let tokens = &transcript.tokens;
+    let start_offset = tokens[0].start_ms;
+    let stop_ms = start_offset + transcript.duration_ms();
+
+    let mut spans = Vec::new();
+    let mut window_start = start_offset;
+    while window_start < stop_ms {
         let window_end = window_start + config.window_size_ms;
+        let mut span = WindowSpan {
+            start: tokens.partition_point(|t| t.start_ms < window_start),
+            end: tokens.partition_point(|t| t.start_ms < window_end),
+            start_ms: window_start,
+            end_ms: window_end,
+        };
 
-        // Find tokens in this window
-        let token_indices: Vec<usize> = transcript
-            .tokens
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| t.start_ms >= window_start && t.start_ms < window_end)
-            .map(|(i, _)| i)
-            .collect();
+        // Start and end at pauses near the fixed edges
+        let start = snap_to_pause(transcript, span.start, config);
+        if start != span.start {
+            span.start = start;
+            span.start_ms = tokens[start].start_ms;
+        }
+        let end = snap_to_pause(transcript, span.end, config);
+        if end != span.end {
+            span.end = end;
+            span.end_ms = tokens[end].start_ms;
+        }
 
-        // Find anchor prefix tokens
-        let anchor_start = window_start.saturating_sub(config.anchor_size_ms);
-        let anchor_prefix_indices: Vec<usize> = transcript
-            .tokens
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| t.start_ms >= anchor_start && t.start_ms < window_start)
-            .map(|(i, _)| i)
-            .collect();
+        let repeated = spans
+            .last()
+            .is_some_and(|prev: &WindowSpan| (prev.start, prev.end) == (span.start, span.end));
+        if span.start < span.end && !repeated {
+            spans.push(span);
+        }
+        window_start += config.stride_ms.max(1);
+    }
+    spans
+}
 
-        // Find anchor suffix tokens
-        let anchor_end = window_end + config.anchor_size_ms;
-        let anchor_suffix_indices: Vec<usize> = transcript
-            .tokens
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| t.start_ms >= window_end && t.start_ms < anchor_end)
-            .map(|(i, _)| i)
-            .collect();
+/// One window per cluster of nearby problem zones
+///
+/// Zones closer than `cluster_gap_ms` are clustered and each cluster is
+/// padded by `context_ms`. Window edges are then moved outwards (by at most
+/// another `context_ms`) to the nearest turn boundary or pause, so windows do
+/// not cut mid-turn. Windows that overlap after this are merged.
+fn adaptive_spans(
+    transcript: &TokenizedTranscript,
+    config: &WindowConfig,
+    problem_zones: &[MergedZone],
+) -> Vec<WindowSpan> {
+    let tokens = &transcript.tokens;
 
-        // Check if window intersects any problem zone
+    let mut zones: Vec<&MergedZone> = problem_zones.iter().collect();
+    zones.sort_by_key(|z| z.start_ms);
+
+    let mut clusters: Vec<(u64, u64)> = Vec::new();
+    for zone in zones {
+        match clusters.last_mut() {
+            Some((_, end)) if zone.start_ms <= *end + config.cluster_gap_ms => {
+                *end = (*end).max(zone.end_ms);
+            }
+            _ => clusters.push((zone.start_ms, zone.end_ms)),
+        }
+    }
+
+    // Token ranges [start, end), merged where padding made them overlap
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (start_ms, end_ms) in clusters {
+        let start = snap_start(transcript, start_ms.saturating_sub(config.context_ms), config);
+        let end = snap_end(transcript, end_ms + config.context_ms, config);
+        if start >= end {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some((_, prev_end)) if start <= *prev_end => *prev_end = (*prev_end).max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| WindowSpan {
+            start,
+            end,
+            start_ms: tokens[start].start_ms,
+            end_ms: tokens[end - 1].end_ms,
+        })
+        .collect()
+}
+
+/// Whether a window may start at token `index`: the transcript edges, a
+/// speaker change, a pause or a turn-taking point before the token
+fn is_window_cut(transcript: &TokenizedTranscript, index: usize, config: &WindowConfig) -> bool {
+    let tokens = &transcript.tokens;
+    if index == 0 || index >= tokens.len() {
+        return true;
+    }
+    let (prev, next) = (&tokens[index - 1], &tokens[index]);
+    prev.speaker != next.speaker
+        || next.is_turn_point
+        || next.pause_before_ms >= config.min_pause_ms
+}
+
+/// Move a sliding window edge at token `index` to the cut within
+/// `pause_snap_ms` with the longest pause, the nearest one on a tie
+fn snap_to_pause(transcript: &TokenizedTranscript, index: usize, config: &WindowConfig) -> usize {
+    let tokens = &transcript.tokens;
+    if config.pause_snap_ms == 0 || index == 0 || index >= tokens.len() {
+        return index;
+    }
+    let at_ms = tokens[index].start_ms;
+    let lo = tokens.partition_point(|t| t.start_ms < at_ms.saturating_sub(config.pause_snap_ms));
+    let hi = tokens.partition_point(|t| t.start_ms <= at_ms + config.pause_snap_ms);
+
+    (lo.max(1)..hi)
+        .filter(|&i| is_window_cut(transcript, i, config))
+        .max_by_key(|&i| (tokens[i].pause_before_ms, std::cmp::Reverse(i.abs_diff(index))))
+        .unwrap_or(index)
+}
+
+/// First token of a window wanted to start at `start_ms`, moved back to a cut
+fn snap_start(transcript: &TokenizedTranscript, start_ms: u64, config: &WindowConfig) -> usize {
+    let tokens = &transcript.tokens;
+    let first = tokens.partition_point(|t| t.start_ms < start_ms);
+    let limit_ms = start_ms.saturating_sub(config.context_ms);
+
+    (0..=first)
+        .rev()
+        .take_while(|&i| i == first || tokens[i].start_ms >= limit_ms)
+        .find(|&i| is_window_cut(transcript, i, config))
+        .unwrap_or(first)
+}
+
+/// End (exclusive) of a window wanted to end at `end_ms`, moved on to a cut
+fn snap_end(transcript: &TokenizedTranscript, end_ms: u64, config: &WindowConfig) -> usize {
+    let tokens = &transcript.tokens;
+    let end = tokens.partition_point(|t| t.start_ms < end_ms);
+    let limit_ms = end_ms + config.context_ms;
+
+    (end..=tokens.len())
+        .take_while(|&i| i == end || tokens[i - 1].start_ms < limit_ms)
+        .find(|&i| is_window_cut(transcript, i, config))
+        .unwrap_or(end)
+}
+
+/// Turns spans into windows and keeps them within the token limits
+struct SpanBuilder<'a> {
+    transcript: &'a TokenizedTranscript,
+    config: &'a WindowConfig,
+    problem_zones: &'a [MergedZone],
+    zone_tokens: &'a HashSet<usize>,
+}
+
+impl SpanBuilder<'_> {
+    /// Build the window for a span, with anchors, problem types and severity
+    fn window(&self, id: usize, span: WindowSpan) -> Window {
+        let tokens = &self.transcript.tokens;
+        let anchor_start = span.start_ms.saturating_sub(self.config.anchor_size_ms);
+        let anchor_end = span.end_ms + self.config.anchor_size_ms;
+
+        let prefix_in_time = (0..span.start)
+            .rev()
+            .take_while(|&i| tokens[i].start_ms >= anchor_start)
+            .count();
+        let suffix_in_time = (span.end..tokens.len())
+            .take_while(|&i| tokens[i].start_ms < anchor_end)
+            .count();
+        let prefix_len = self.anchor_len(prefix_in_time, span.start);
+        let suffix_len = self.anchor_len(suffix_in_time, tokens.len() - span.end);
+
+        let token_indices: Vec<usize> = (span.start..span.end).collect();
         let (is_problem_zone, problem_types) =
-            check_problem_intersection(window_start, window_end, problem_zones);
-
-        if !token_indices.is_empty() {
-            windows.push(Window {
-                window_id: format!("w_{}", window_id),
-                start_ms: window_start,
-                end_ms: window_end,
-                token_indices,
-                anchor_prefix_indices,
-                anchor_suffix_indices,
-                is_problem_zone,
-                problem_types,
-            });
-            window_id += 1;
+            check_problem_intersection(span.start_ms, span.end_ms, self.problem_zones);
+        let severity =
+            window_severity(self.transcript, &token_indices, &problem_types, self.zone_tokens);
+
+        Window {
+            window_id: format!("w_{}", id),
+            start_ms: span.start_ms,
+            end_ms: span.end_ms,
+            token_indices,
+            anchor_prefix_indices: (span.start - prefix_len..span.start).collect(),
+            anchor_suffix_indices: (span.end..span.end + suffix_len).collect(),
+            is_problem_zone,
+            problem_types,
+            severity,
         }
+    }
 
-        window_start += config.stride_ms;
+    /// Anchor length from the tokens within `anchor_size_ms`, bounded by the
+    /// anchor token limits and the tokens available
+    fn anchor_len(&self, in_time: usize, available: usize) -> usize {
+        let len = self.config.min_anchor_tokens.map_or(in_time, |min| in_time.max(min));
+        let len = self.config.max_anchor_tokens.map_or(len, |max| len.min(max));
+        len.min(available)
     }
 
-    // Identify problem windows
-    let problem_window_indices: Vec<usize> = if config.filter_problem_zones {
-        windows
-            .iter()
-            .enumerate()
-            .filter(|(_, w)| w.is_problem_zone)
-            .map(|(i, _)| i)
-            .collect()
+    /// Estimated tokens of the Stage 1 user prompt for a span
+    fn prompt_tokens(&self, span: WindowSpan) -> u32 {
+        let prompt = build_window_prompt(
+            self.transcript,
+            &self.window(0, span),
+            ESTIMATE_EDIT_BUDGET_PERCENT,
+        );
+        estimate_tokens(&prompt)
+    }
+
+    fn fits(&self, span: WindowSpan) -> bool {
+        self.config.max_window_tokens.is_none_or(|max| span.len() <= max)
+            && self.config.max_prompt_tokens.is_none_or(|max| self.prompt_tokens(span) <= max)
+    }
+
+    /// Split spans over `max_window_tokens` or `max_prompt_tokens`, then merge
+    /// spans under `min_window_tokens` into the span they touch
+    fn fit_to_limits(&self, spans: Vec<WindowSpan>) -> Vec<WindowSpan> {
+        let spans: Vec<WindowSpan> = spans
+            .into_iter()
+            .flat_map(|span| self.split_to_token_limit(span))
+            .flat_map(|span| self.split_to_prompt_limit(span))
+            .collect();
+        self.merge_small(spans)
+    }
+
+    /// Split into pieces of at most `max_window_tokens`, cutting at the last
+    /// turn boundary or pause in the second half of each piece
+    fn split_to_token_limit(&self, span: WindowSpan) -> Vec<WindowSpan> {
+        let Some(max) = self.config.max_window_tokens.filter(|&m| m > 0) else {
+            return vec![span];
+        };
+
+        let mut pieces = Vec::new();
+        let mut rest = span;
+        while rest.len() > max {
+            let hard_cut = rest.start + max;
+            let cut = (rest.start + max.div_ceil(2)..=hard_cut)
+                .rev()
+                .find(|&i| is_window_cut(self.transcript, i, self.config))
+                .unwrap_or(hard_cut);
+            let (piece, tail) = self.split_at(rest, cut);
+            pieces.push(piece);
+            rest = tail;
+        }
+        pieces.push(rest);
+        pieces
+    }
+
+    /// Halve until each piece's prompt fits in `max_prompt_tokens`, cutting at
+    /// the turn boundary or pause closest to the middle
+    fn split_to_prompt_limit(&self, span: WindowSpan) -> Vec<WindowSpan> {
+        let Some(max) = self.config.max_prompt_tokens else {
+            return vec![span];
+        };
+        if span.len() < 2 || self.prompt_tokens(span) <= max {
+            return vec![span];
+        }
+
+        let mid = span.start + span.len() / 2;
+        let margin = (span.len() / 4).max(1);
+        let cut = (span.start + margin..=span.end - margin)
+            .filter(|&i| is_window_cut(self.transcript, i, self.config))
+            .min_by_key(|&i| i.abs_diff(mid))
+            .unwrap_or(mid);
+
+        let (head, tail) = self.split_at(span, cut);
+        let mut pieces = self.split_to_prompt_limit(head);
+        pieces.extend(self.split_to_prompt_limit(tail));
+        pieces
+    }
+
+    /// Split a span before token `cut`
+    fn split_at(&self, span: WindowSpan, cut: usize) -> (WindowSpan, WindowSpan) {
+        let cut_ms = self.transcript.tokens[cut].start_ms;
+        (
+            WindowSpan {
+                end: cut,
+                end_ms: cut_ms,
+                ..span
+            },
+            WindowSpan {
+                start: cut,
+                start_ms: cut_ms,
+                ..span
+            },
+        )
+    }
+
+    /// Merge spans with fewer than `min_window_tokens` into the previous span
+    /// they touch or overlap, if the merged span still fits the limits
+    fn merge_small(&self, spans: Vec<WindowSpan>) -> Vec<WindowSpan> {
+        let Some(min) = self.config.min_window_tokens else {
+            return spans;
+        };
+
+        let mut merged: Vec<WindowSpan> = Vec::new();
+        for span in spans {
+            if let Some(prev) = merged.last_mut()
+                && span.start <= prev.end
+                && (prev.len() < min || span.len() < min)
+            {
+                let candidate = WindowSpan {
+                    start: prev.start,
+                    end: prev.end.max(span.end),
+                    start_ms: prev.start_ms,
+                    end_ms: prev.end_ms.max(span.end_ms),
+                };
+                if self.fits(candidate) {
+                    *prev = candidate;
+                    continue;
+                }
+            }
+            merged.push(span);
+        }
+        merged
+    }
+}
+
+/// Score how badly a window needs fixing (0.0-1.0)
+///
+/// Averages four components, each 0.0-1.0: the summed weight of the problem
+/// types present (relative to all types), the fraction of tokens inside a
+/// problem zone, the speaker switch rate, and one minus the mean speaker
+/// confidence.
+fn window_severity(
+    transcript: &TokenizedTranscript,
+    token_indices: &[usize],
+    problem_types: &[ProblemType],
+    zone_tokens: &HashSet<usize>,
+) -> f64 {
+    if token_indices.is_empty() {
+        return 0.0;
+    }
+
+    let type_score = ProblemType::combined_weight(problem_types);
+
+    let n = token_indices.len() as f64;
+    let density = token_indices.iter().filter(|i| zone_tokens.contains(i)).count() as f64 / n;
+
+    let switches = token_indices
+        .windows(2)
+        .filter(|pair| transcript.tokens[pair[0]].speaker != transcript.tokens[pair[1]].speaker)
+        .count();
+    let switch_rate = switches as f64 / (n - 1.0).max(1.0);
+
+    // Unknown confidence counts as neither confident nor doubtful
+    let known: Vec<f64> = token_indices
+        .iter()
+        .filter_map(|&i| transcript.tokens[i].speaker_confidence())
+        .collect();
+    let doubt = if known.is_empty() {
+        0.5
     } else {
-        (0..windows.len()).collect()
+        (1.0 - known.iter().sum::<f64>() / known.len() as f64).clamp(0.0, 1.0)
     };
 
-    WindowSet {
-        windows,
-        problem_window_indices,
-    }
+    (type_score + density + switch_rate + doubt) / 4.0
 }
 
-/// Check if a window intersects any problem zone
+/// Check if a window intersects any merged problem zone, collecting the
+/// problem types of every zone it touches
 fn check_problem_intersection(
     window_start: u64,
     window_end: u64,
-    problem_zones: &[ProblemZone],
+    problem_zones: &[MergedZone],
 ) -> (bool, Vec<ProblemType>) {
     let mut types = Vec::new();
 
     for zone in problem_zones {
         // Check for overlap
         if zone.start_ms < window_end && zone.end_ms > window_start {
-            if !types.contains(&zone.problem_type) {
-                types.push(zone.problem_type);
+            for problem_type in &zone.problem_types {
+                if !types.contains(problem_type) {
+                    types.push(*problem_type);
+                }
             }
         }
     }
@@ -410,4 +1029,330 @@ mod tests {
 
         assert!(!short_turn_zones.is_empty());
     }
+
+    #[test]
+    fn test_detect_low_transcription_confidence() {
+        // "uh" alone is unsure; "mm hm what" is a run of unsure words
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.2, "confidence": 0.95, "speaker": 0},
+                            {"word": "uh", "start": 0.3, "end": 0.4, "confidence": 0.3, "speaker": 0},
+                            {"word": "then", "start": 0.5, "end": 0.7, "confidence": 0.95, "speaker": 0},
+                            {"word": "mm", "start": 0.8, "end": 0.9, "confidence": 0.2, "speaker": 0},
+                            {"word": "hm", "start": 1.0, "end": 1.1, "confidence": 0.4, "speaker": 0},
+                            {"word": "what", "start": 1.2, "end": 1.4, "confidence": 0.45, "speaker": 0},
+                            {"word": "now", "start": 1.5, "end": 1.7, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let transcript = parse_deepgram_json(json).unwrap();
+
+        let zones = detect_low_transcription_confidence(&transcript, &ProblemZoneConfig::default());
+
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].problem_type, ProblemType::LowTranscriptionConfidence);
+        assert_eq!(zones[0].token_indices, [3, 4, 5]);
+        assert_eq!((zones[0].start_ms, zones[0].end_ms), (800, 1400));
+    }
+
+    #[test]
+    fn test_estimate_missing_speaker_confidence() {
+        // No speaker_confidence except on the last word; speaker 1 interjects
+        // once in the middle of speaker 0's talk
+        let mut words: Vec<String> = (0..12)
+            .map(|i| {
+                format!(
+                    r#"{{"word": "w", "start": {}.0, "end": {}.8, "confidence": 0.9, "speaker": {}}}"#,
+                    i,
+                    i,
+                    u32::from(i == 6)
+                )
+            })
+            .collect();
+        words.push(
+            r#"{"word": "end", "start": 12.0, "end": 12.8, "confidence": 0.9, "speaker": 0, "speaker_confidence": 0.9}"#
+                .to_string(),
+        );
+        let json = format!(
+            r#"{{"results": {{"channels": [{{"alternatives": [{{"words": [{}]}}]}}]}}}}"#,
+            words.join(",")
+        );
+        let mut transcript = parse_deepgram_json(&json).unwrap();
+        let config = ProblemZoneConfig::default();
+        let result = normalize(&mut transcript, &WindowConfig::default(), &config);
+
+        let conf: Vec<f64> = transcript
+            .tokens
+            .iter()
+            .map(|t| t.speaker_confidence().unwrap())
+            .collect();
+        assert!(transcript.tokens[..12].iter().all(|t| t.is_speaker_conf_estimated()));
+        assert!(conf[0] > conf[5] && conf[5] > conf[6]);
+        assert_eq!(transcript.tokens[12].estimated_speaker_conf, None);
+        assert_eq!(conf[12], 0.9);
+
+        // Only words near the interjection look doubtful
+        let low: Vec<usize> = result
+            .problem_zones
+            .iter()
+            .filter(|z| z.problem_type == ProblemType::LowConfidence)
+            .flat_map(|z| z.token_indices.clone())
+            .collect();
+        assert!(low.contains(&6));
+        assert!(!low.contains(&0) && !low.contains(&12));
+    }
+
+    #[test]
+    fn test_merge_problem_zones() {
+        let zone = |start_s: u64, end_s: u64, problem_type| ProblemZone {
+            start_ms: start_s * 1000,
+            end_ms: end_s * 1000,
+            problem_type,
+            token_indices: (start_s as usize..end_s as usize).collect(),
+        };
+        let zones = vec![
+            zone(5, 15, ProblemType::SpeakerJitter),
+            zone(20, 22, ProblemType::LowConfidence),
+            zone(0, 10, ProblemType::SpeakerJitter),
+            zone(12, 13, ProblemType::ShortTurn),
+        ];
+
+        let merged = merge_problem_zones(&zones);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!((merged[0].start_ms, merged[0].end_ms), (0, 15_000));
+        assert_eq!(merged[0].problem_types, [ProblemType::SpeakerJitter, ProblemType::ShortTurn]);
+        assert_eq!(merged[0].zone_count, 3);
+        assert_eq!(merged[0].token_indices, (0..15).collect::<Vec<_>>());
+        assert!(merged[0].score > merged[1].score);
+        assert_eq!(merged[1].problem_types, [ProblemType::LowConfidence]);
+    }
+
+    #[test]
+    fn test_overlap_vs_tight_handoff() {
+        // Speaker 1's "yeah" is listed after speaker 0's words, as it would be
+        // when channels are concatenated, but is spoken during "absolutely"
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "absolutely", "start": 0.0, "end": 1.0, "confidence": 0.95, "speaker": 0},
+                            {"word": "fine", "start": 1.0, "end": 1.5, "confidence": 0.95, "speaker": 0},
+                            {"word": "yeah", "start": 0.5, "end": 0.8, "confidence": 0.95, "speaker": 1},
+                            {"word": "so", "start": 2.0, "end": 2.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "right", "start": 2.35, "end": 2.6, "confidence": 0.95, "speaker": 1}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        detect_overlap_regions(&mut transcript, &ProblemZoneConfig::default());
+
+        let overlap: Vec<bool> = transcript.tokens.iter().map(|t| t.is_overlap_region).collect();
+        let handoff: Vec<bool> = transcript.tokens.iter().map(|t| t.is_tight_handoff).collect();
+        assert_eq!(overlap, [true, false, true, false, false]);
+        assert_eq!(handoff, [false, false, false, true, true]);
+
+        // Below the overlap threshold, the same words are a handoff at most
+        let lenient = ProblemZoneConfig {
+            min_overlap_ms: 500,
+            ..Default::default()
+        };
+        detect_overlap_regions(&mut transcript, &lenient);
+        assert!(transcript.tokens.iter().all(|t| !t.is_overlap_region));
+    }
+
+    #[test]
+    fn test_tight_handoffs_seed_zones_on_sequential_input() {
+        // Mono input: words never overlap, but speakers change within 50ms
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "did", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "you", "start": 0.3, "end": 0.5, "confidence": 0.95, "speaker": 0},
+                            {"word": "see", "start": 0.5, "end": 0.8, "confidence": 0.95, "speaker": 0},
+                            {"word": "it", "start": 0.8, "end": 1.0, "confidence": 0.95, "speaker": 0},
+                            {"word": "yes", "start": 1.05, "end": 1.4, "confidence": 0.95, "speaker": 1},
+                            {"word": "twice", "start": 1.4, "end": 1.9, "confidence": 0.95, "speaker": 1},
+                            {"word": "later", "start": 6.0, "end": 6.5, "confidence": 0.95, "speaker": 1}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let config = ProblemZoneConfig::default();
+        detect_overlap_regions(&mut transcript, &config);
+
+        assert!(detect_overlap_adjacent(&transcript, &config).is_empty());
+        let zones = detect_handoff_adjacent(&transcript, &config);
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].problem_type, ProblemType::HandoffAdjacent);
+        assert_eq!(zones[0].token_indices, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_detect_pauses_and_snap_windows() {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "anyway", "start": 0.5, "end": 0.8, "confidence": 0.95, "speaker": 0},
+                            {"word": "well", "start": 2.0, "end": 2.3, "confidence": 0.95, "speaker": 0},
+                            {"word": "right", "start": 2.9, "end": 3.1, "confidence": 0.95, "speaker": 1},
+                            {"word": "okay", "start": 3.2, "end": 3.5, "confidence": 0.95, "speaker": 1}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let config = WindowConfig {
+            window_size_ms: 2_500,
+            stride_ms: 2_500,
+            filter_problem_zones: false,
+            ..Default::default()
+        };
+        let result = normalize(&mut transcript, &config, &ProblemZoneConfig::default());
+
+        // A long pause, and a shorter one at a speaker change
+        assert_eq!(result.pauses.len(), 2);
+        assert_eq!(result.pauses[0].duration_ms(), 1_200);
+        assert!(!result.pauses[0].speaker_change);
+        assert!(result.pauses[1].speaker_change);
+        let turn_points: Vec<bool> = transcript.tokens.iter().map(|t| t.is_turn_point).collect();
+        assert_eq!(turn_points, [false, false, true, true, false]);
+
+        // Sliding window edges move to the long pause
+        let windows = &result.windows.windows;
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].token_indices, vec![0, 1]);
+        assert_eq!(windows[1].token_indices, vec![2, 3, 4]);
+    }
+
+    /// One word a second for 120s: speaker 0 from 0-30s, 1 from 30-60s, 0
+    /// from 60-120s
+    fn one_word_per_second() -> TokenizedTranscript {
+        let words: Vec<String> = (0..120)
+            .map(|i| {
+                let speaker = if (30..60).contains(&i) { 1 } else { 0 };
+                format!(
+                    r#"{{"word": "w{}", "start": {}.0, "end": {}.8, "confidence": 0.9, "speaker": {}}}"#,
+                    i, i, i, speaker
+                )
+            })
+            .collect();
+        let json = format!(
+            r#"{{"results": {{"channels": [{{"alternatives": [{{"words": [{}]}}]}}]}}}}"#,
+            words.join(",")
+        );
+        parse_deepgram_json(&json).unwrap()
+    }
+
+    #[test]
+    fn test_adaptive_windows_cluster_and_snap() {
+        let transcript = one_word_per_second();
+        let zone = |start_ms: u64, end_ms: u64| ProblemZone {
+            start_ms,
+            end_ms,
+            problem_type: ProblemType::ShortTurn,
+            token_indices: vec![(start_ms / 1000) as usize],
+        };
+        let zones = vec![zone(36_000, 37_000), zone(40_000, 41_000), zone(100_000, 101_000)];
+        let config = WindowConfig {
+            strategy: WindowStrategy::Adaptive,
+            context_ms: 5_000,
+            ..Default::default()
+        };
+
+        let windows = build_windows(&transcript, &config, &merge_problem_zones(&zones));
+
+        // The two nearby zones share a window, padded to 31s and snapped back
+        // to the turn start at 30s; the far zone gets its own window
+        assert_eq!(windows.total_windows(), 2);
+        assert_eq!(windows.windows[0].token_indices.first(), Some(&30));
+        assert_eq!(windows.windows[0].token_indices.last(), Some(&45));
+        assert_eq!(windows.windows[1].start_ms, 95_000);
+        assert_eq!(windows.problem_window_count(), 2);
+
+        let split = WindowConfig {
+            max_window_tokens: Some(8),
+            ..config
+        };
+        let windows = build_windows(&transcript, &split, &merge_problem_zones(&zones));
+        assert!(windows.windows.iter().all(|w| w.token_count() <= 8));
+    }
+
+    #[test]
+    fn test_window_token_limits() {
+        let transcript = one_word_per_second();
+        let zones = vec![];
+        let config = WindowConfig {
+            window_size_ms: 10_000,
+            stride_ms: 10_000,
+            filter_problem_zones: false,
+            ..Default::default()
+        };
+
+        // Sparse 10-token windows are merged in pairs
+        let merged = WindowConfig {
+            min_window_tokens: Some(15),
+            max_window_tokens: Some(20),
+            ..config.clone()
+        };
+        let windows = build_windows(&transcript, &merged, &zones);
+        assert_eq!(windows.total_windows(), 6);
+        assert!(windows.windows.iter().all(|w| w.token_count() == 20));
+
+        // Anchors are sized by tokens as well as time
+        let anchored = WindowConfig {
+            min_anchor_tokens: Some(8),
+            max_anchor_tokens: Some(3),
+            ..config.clone()
+        };
+        let windows = build_windows(&transcript, &anchored, &zones);
+        assert_eq!(windows.windows[1].anchor_prefix_indices, vec![7, 8, 9]);
+        let anchored = WindowConfig {
+            min_anchor_tokens: Some(8),
+            ..config.clone()
+        };
+        let windows = build_windows(&transcript, &anchored, &zones);
+        assert_eq!(windows.windows[1].anchor_prefix_indices.len(), 8);
+
+        // Prompts over the limit are halved, at the turn change where possible
+        let limited = WindowConfig {
+            window_size_ms: 120_000,
+            max_prompt_tokens: Some(1_500),
+            ..config
+        };
+        let builder = SpanBuilder {
+            transcript: &transcript,
+            config: &limited,
+            problem_zones: &zones,
+            zone_tokens: &HashSet::new(),
+        };
+        let windows = build_windows(&transcript, &limited, &zones);
+        assert!(windows.total_windows() > 1);
+        assert!(windows.windows.iter().any(|w| w.token_indices.first() == Some(&60)));
+        for w in &windows.windows {
+            let span = WindowSpan {
+                start: w.token_indices[0],
+                end: w.token_indices[w.token_count() - 1] + 1,
+                start_ms: w.start_ms,
+                end_ms: w.end_ms,
+            };
+            assert!(builder.prompt_tokens(span) <= 1_500);
+        }
+    }
 }
@@ -2,7 +2,8 @@ use anyhow::Result;
 use tracing::{info, warn};
 
 use crate::llm::{
-    build_window_prompt, validate_patch, AnthropicClient, Usage, ValidationConfig, SYSTEM_PROMPT,
+    build_window_prompt, validate_patch, AnthropicClient, CostEstimate, ModelPricing, Usage,
+    ValidationConfig, WindowCostEstimate, SYSTEM_PROMPT,
 };
 use crate::models::{TokenizedTranscript, Window, WindowPatch, WindowSet};
 
@@ -15,6 +16,12 @@ pub struct Stage1Config {
     pub validation: ValidationConfig,
     /// Maximum retries per window on validation failure
     pub max_retries: u32,
+    /// Spending limits for the whole stage
+    pub budget: Stage1Budget,
+    /// Only process the N most severe problem windows
+    pub max_windows: Option<usize>,
+    /// Only process problem windows with at least this severity (0.0-1.0)
+    pub min_severity: Option<f64>,
 }
 
 impl Default for Stage1Config {
@@ -23,32 +30,100 @@ impl Default for Stage1Config {
             edit_budget_percent: 3.0,
             validation: ValidationConfig::default(),
             max_retries: 2,
+            budget: Stage1Budget::default(),
+            max_windows: None,
+            min_severity: None,
         }
     }
 }
 
+/// Spending limits for Stage 1; limits that are not set are not enforced
+///
+/// Usage is checked before each request, so a run stops within one
+/// request's usage of the limit.
+#[derive(Debug, Clone, Default)]
+pub struct Stage1Budget {
+    /// Maximum cumulative input tokens
+    pub max_input_tokens: Option<u64>,
+    /// Maximum cumulative output tokens
+    pub max_output_tokens: Option<u64>,
+    /// Maximum cumulative cost in USD
+    pub max_cost_usd: Option<f64>,
+}
+
+impl Stage1Budget {
+    /// Whether any limit is set
+    pub fn is_set(&self) -> bool {
+        self.max_input_tokens.is_some()
+            || self.max_output_tokens.is_some()
+            || self.max_cost_usd.is_some()
+    }
+
+    /// Describe the first limit that the usage has reached, if any
+    pub fn exhausted(&self, usage: &Usage, pricing: &ModelPricing) -> Option<String> {
+        if let Some(max) = self.max_input_tokens
+            && usage.input_tokens as u64 >= max
+        {
+            return Some(format!("{} input tokens used (limit {})", usage.input_tokens, max));
+        }
+        if let Some(max) = self.max_output_tokens
+            && usage.output_tokens as u64 >= max
+        {
+            return Some(format!("{} output tokens used (limit {})", usage.output_tokens, max));
+        }
+        if let Some(max) = self.max_cost_usd {
+            let cost = pricing.usage_cost(usage);
+            if cost >= max {
+                return Some(format!("${:.4} spent (limit ${:.4})", cost, max));
+            }
+        }
+        None
+    }
+}
+
 /// Result of Stage 1 processing
 #[derive(Debug)]
 pub struct Stage1Result {
-    /// Collected patches from all windows
+    /// Validated patches from all processed windows, including windows that
+    /// made no changes (Stage 2 counts those as votes to keep current labels)
     pub patches: Vec<WindowPatch>,
-    /// Number of windows processed
+    /// Number of windows sent to the LLM, including windows whose patch was
+    /// rejected (see `validation_failures`)
     pub windows_processed: usize,
-    /// Number of windows skipped (no problem zones)
+    /// Number of windows skipped because they contain no problem zones
     pub windows_skipped: usize,
-    /// Number of validation failures
+    /// Number of problem windows dropped by `max_windows` or `min_severity`
+    pub windows_filtered: usize,
+    /// Number of windows whose patch was rejected after all retries
     pub validation_failures: usize,
+    /// Windows whose patch was rejected after all retries
+    pub rejected_windows: Vec<RejectedWindow>,
+    /// IDs of problem windows not sent, or not retried, because the budget
+    /// ran out
+    pub budget_skipped_windows: Vec<String>,
     /// Total API token usage
     pub usage: Usage,
 }
 
+/// A window whose patch failed validation or whose request failed
+#[derive(Debug, Clone)]
+pub struct RejectedWindow {
+    /// ID of the rejected window
+    pub window_id: String,
+    /// Last error reported for the window
+    pub error: String,
+}
+
 /// Execute Stage 1: LLM relabeling
 ///
-/// For each window that intersects a problem zone:
+/// For each selected problem window, most severe first:
 /// 1. Build the prompt with tokens and constraints
 /// 2. Call Claude API with tool use
 /// 3. Validate the returned patch
 /// 4. Collect valid patches for reconciliation
+///
+/// With a budget set, the remaining windows are skipped once a limit is
+/// reached.
 pub async fn execute_stage1(
     client: &AnthropicClient,
     transcript: &TokenizedTranscript,
@@ -57,21 +132,45 @@ pub async fn execute_stage1(
 ) -> Result<Stage1Result> {
     let mut patches = Vec::new();
     let mut validation_failures = 0;
+    let mut rejected_windows = Vec::new();
     let mut total_usage = Usage::default();
+    let mut budget_skipped_windows = Vec::new();
+    let pricing = ModelPricing::for_model(client.model()).unwrap_or_default();
 
-    let problem_windows: Vec<&Window> = windows.problem_windows().collect();
+    let problem_windows = windows.ranked_problem_windows(config.max_windows, config.min_severity);
     let problem_window_count = problem_windows.len();
-    let windows_skipped = windows.total_windows() - problem_window_count;
+    let windows_skipped = windows.total_windows() - windows.problem_window_count();
+    let windows_filtered = windows.problem_window_count() - problem_window_count;
 
     info!(
-        "Stage 1: Processing {} problem windows ({} skipped)",
+        "Stage 1: Processing {} problem windows ({} without problems, {} filtered by severity)",
         problem_window_count,
-        windows_skipped
+        windows_skipped,
+        windows_filtered
     );
 
     for window in problem_windows {
-        match process_window(client, transcript, window, config, &mut total_usage).await {
-            Ok(patch) => {
+        if let Some(reason) = config.budget.exhausted(&total_usage, &pricing) {
+            if budget_skipped_windows.is_empty() {
+                warn!("Stage 1 budget exhausted: {}", reason);
+            }
+            budget_skipped_windows.push(window.window_id.clone());
+            continue;
+        }
+
+        match process_window(client, transcript, window, config, &pricing, &mut total_usage)
+            .await
+        {
+            Ok(WindowOutcome::BudgetExhausted(reason)) => {
+                if budget_skipped_windows.is_empty() {
+                    warn!(
+                        "Stage 1 budget exhausted before retrying {}: {}",
+                        window.window_id, reason
+                    );
+                }
+                budget_skipped_windows.push(window.window_id.clone());
+            }
+            Ok(WindowOutcome::Patch(patch)) => {
                 if !patch.is_empty() {
                     info!(
                         "Window {}: {} relabels, {} turn edits",
@@ -79,56 +178,118 @@ pub async fn execute_stage1(
                         patch.relabel_count(),
                         patch.turn_edits.len()
                     );
-                    patches.push(patch);
                 } else {
                     info!("Window {}: no changes", window.window_id);
                 }
+                patches.push(patch);
             }
             Err(e) => {
                 warn!("Window {} failed: {}", window.window_id, e);
                 validation_failures += 1;
+                rejected_windows.push(RejectedWindow {
+                    window_id: window.window_id.clone(),
+                    error: e.to_string(),
+                });
             }
         }
     }
 
+    if !budget_skipped_windows.is_empty() {
+        warn!(
+            "Skipped {} windows due to budget: {}",
+            budget_skipped_windows.len(),
+            budget_skipped_windows.join(", ")
+        );
+    }
+
     Ok(Stage1Result {
-        windows_processed: problem_window_count,
+        windows_processed: problem_window_count - budget_skipped_windows.len(),
         windows_skipped,
+        windows_filtered,
         patches,
         validation_failures,
+        rejected_windows,
+        budget_skipped_windows,
         usage: total_usage,
     })
 }
 
+/// Estimate Stage 1 token usage and cost without calling the API
+///
+/// Builds the same prompt `execute_stage1` would send for every selected
+/// problem window. Models without known pricing are priced like the default model.
+pub fn estimate_stage1(
+    transcript: &TokenizedTranscript,
+    windows: &WindowSet,
+    config: &Stage1Config,
+    model: &str,
+) -> CostEstimate {
+    let pricing = ModelPricing::for_model(model).unwrap_or_else(|| {
+        warn!("No pricing known for model {}, using default model pricing", model);
+        ModelPricing::default()
+    });
+
+    let windows = windows
+        .ranked_problem_windows(config.max_windows, config.min_severity)
+        .into_iter()
+        .map(|window| {
+            let prompt = build_window_prompt(transcript, window, config.edit_budget_percent);
+            let edit_budget =
+                (window.token_count() as f64 * config.edit_budget_percent / 100.0).ceil();
+            WindowCostEstimate::new(&window.window_id, &prompt, edit_budget as usize)
+        })
+        .collect();
+
+    CostEstimate {
+        model: model.to_string(),
+        pricing,
+        max_retries: config.max_retries,
+        windows,
+    }
+}
+
+/// How processing a single window ended, short of an error
+enum WindowOutcome {
+    /// A validated patch
+    Patch(WindowPatch),
+    /// The budget ran out before a retry; the window counts as skipped
+    BudgetExhausted(String),
+}
+
 /// Process a single window
 async fn process_window(
     client: &AnthropicClient,
     transcript: &TokenizedTranscript,
     window: &Window,
     config: &Stage1Config,
+    pricing: &ModelPricing,
     total_usage: &mut Usage,
-) -> Result<WindowPatch> {
+) -> Result<WindowOutcome> {
     let prompt = build_window_prompt(transcript, window, config.edit_budget_percent);
 
     let mut last_error = None;
 
     for attempt in 0..=config.max_retries {
         if attempt > 0 {
+            if let Some(reason) = config.budget.exhausted(total_usage, pricing) {
+                return Ok(WindowOutcome::BudgetExhausted(reason));
+            }
             info!(
                 "Window {}: retry {} of {}",
                 window.window_id, attempt, config.max_retries
             );
         }
 
-        match client.send_with_tool(SYSTEM_PROMPT, &prompt).await {
-            Ok((patch, usage)) => {
+        match client.send_with_tool(&SYSTEM_PROMPT, &prompt).await {
+            Ok((mut patch, usage)) => {
                 total_usage.add(&usage);
 
                 // Validate the patch
                 let validation = validate_patch(&patch, transcript, window, &config.validation);
 
                 if validation.is_valid {
-                    return Ok(patch);
+                    patch.quality = validation.quality;
+                    return Ok(WindowOutcome::Patch(patch));
                 } else {
                     last_error = Some(anyhow::anyhow!(
                         "Validation failed: {:?}",
@@ -158,5 +319,30 @@ mod tests {
         let config = Stage1Config::default();
         assert_eq!(config.edit_budget_percent, 3.0);
         assert_eq!(config.max_retries, 2);
+        assert!(!config.budget.is_set());
+    }
+
+    #[test]
+    fn test_budget_exhausted() {
+        let pricing = ModelPricing::default();
+        let usage = Usage {
+            input_tokens: 500_000,
+            output_tokens: 10_000,
+        };
+
+        assert!(Stage1Budget::default().exhausted(&usage, &pricing).is_none());
+
+        let tokens = Stage1Budget {
+            max_output_tokens: Some(20_000),
+            ..Default::default()
+        };
+        assert!(tokens.exhausted(&usage, &pricing).is_none());
+
+        // 0.5 + 0.05 USD at default pricing
+        let dollars = Stage1Budget {
+            max_cost_usd: Some(0.5),
+            ..Default::default()
+        };
+        assert!(dollars.exhausted(&usage, &pricing).unwrap().contains("$0.5500"));
     }
 }
@@ -1,21 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use tracing::info;
+use serde::Serialize;
+use tracing::{debug, info, warn};
 
 use crate::heuristics::micro_turns::rebuild_turns;
-use crate::models::{TokenizedTranscript, WindowPatch, WindowSet};
+use crate::models::{
+    ChangeStage, ReasonCode, Token, TokenizedTranscript, WindowPatch, WindowQuality, WindowSet,
+};
+
+use super::stage2_smoothing::{viterbi_decode, SmoothingConfig};
 
 /// Configuration for Stage 2 reconciliation
 #[derive(Debug, Clone)]
 pub struct Stage2Config {
     /// Minimum turn duration in milliseconds
     pub min_turn_duration_ms: u64,
-    /// Maximum speaker switches per second (soft limit)
+    /// Maximum speaker switches per second within any switch-rate region
     pub max_switches_per_second: f64,
     /// Minimum confidence to protect stable spans
     pub stable_span_confidence: f64,
     /// Minimum windows agreeing to override stable span
     pub min_windows_for_override: usize,
+    /// Formula used to weight each window's vote
+    pub vote_weighting: VoteWeighting,
+    /// Count windows that left a token unchanged as votes for its current speaker
+    pub count_keep_votes: bool,
+    /// Length of the sliding region used to measure local switch rate
+    pub switch_rate_window_ms: u64,
+    /// How candidate labels are turned into final labels
+    pub strategy: ReconciliationStrategy,
+    /// Parameters for the Viterbi strategy
+    pub smoothing: SmoothingConfig,
+}
+
+/// Strategy for choosing final labels from candidates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReconciliationStrategy {
+    /// Independent weighted vote for each token with candidates
+    #[default]
+    WeightedVote,
+    /// Global HMM smoothing of the whole label sequence
+    Viterbi,
 }
 
 impl Default for Stage2Config {
@@ -25,10 +50,87 @@ impl Default for Stage2Config {
             max_switches_per_second: 2.0,
             stable_span_confidence: 0.8,
             min_windows_for_override: 2,
+            vote_weighting: VoteWeighting::default(),
+            count_keep_votes: true,
+            switch_rate_window_ms: 5_000,
+            strategy: ReconciliationStrategy::default(),
+            smoothing: SmoothingConfig::default(),
         }
     }
 }
 
+/// Vote weighting formula for reconciliation
+///
+/// `weight = proximity^a * llm_confidence^b * window_quality^c * uncertainty`,
+/// where `uncertainty` is `1 - uncertainty_penalty` for tokens the LLM listed
+/// in `notes.uncertain_tokens` and 1.0 otherwise.
+#[derive(Debug, Clone)]
+pub struct VoteWeighting {
+    /// Exponent applied to proximity to window center (a)
+    pub proximity_exponent: f64,
+    /// Exponent applied to the LLM-reported relabel confidence (b)
+    pub confidence_exponent: f64,
+    /// Exponent applied to the window quality score (c)
+    pub quality_exponent: f64,
+    /// Confidence assumed when the LLM does not report one
+    pub default_confidence: f64,
+    /// Cost increase at which window quality drops to zero
+    pub max_cost_delta: f64,
+    /// Fraction of weight removed for tokens the LLM marked uncertain
+    pub uncertainty_penalty: f64,
+}
+
+impl Default for VoteWeighting {
+    fn default() -> Self {
+        Self {
+            proximity_exponent: 1.0,
+            confidence_exponent: 1.0,
+            quality_exponent: 1.0,
+            default_confidence: 0.7,
+            max_cost_delta: 20.0,
+            uncertainty_penalty: 0.5,
+        }
+    }
+}
+
+impl VoteWeighting {
+    /// Score a window patch from its validation margin and cost delta
+    ///
+    /// Patches that use their whole edit budget score half of an untouched
+    /// budget; cost increases scale the score down linearly, cost decreases
+    /// raise it (capped at 2x).
+    pub fn window_quality(&self, quality: &WindowQuality) -> f64 {
+        let margin = 0.5 + 0.5 * quality.budget_margin.clamp(0.0, 1.0);
+        let cost = if self.max_cost_delta > 0.0 {
+            (1.0 - quality.cost_delta / self.max_cost_delta).clamp(0.0, 2.0)
+        } else {
+            1.0
+        };
+        margin * cost
+    }
+
+    /// Compute the vote weight for a single candidate label
+    pub fn weight(
+        &self,
+        proximity: f64,
+        confidence: Option<f64>,
+        quality: &WindowQuality,
+        uncertain: bool,
+    ) -> f64 {
+        let confidence = confidence.unwrap_or(self.default_confidence).clamp(0.0, 1.0);
+        let uncertainty = if uncertain {
+            1.0 - self.uncertainty_penalty.clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        proximity.powf(self.proximity_exponent)
+            * confidence.powf(self.confidence_exponent)
+            * self.window_quality(quality).powf(self.quality_exponent)
+            * uncertainty
+    }
+}
+
 /// Result of Stage 2 reconciliation
 #[derive(Debug)]
 pub struct Stage2Result {
@@ -36,6 +138,74 @@ pub struct Stage2Result {
     pub tokens_relabeled: usize,
     /// Number of conflicts resolved
     pub conflicts_resolved: usize,
+    /// IDs of the tokens whose candidate labels conflicted
+    pub conflict_token_ids: Vec<String>,
+    /// Every label change forced by post-reconciliation constraints
+    pub constraint_changes: Vec<ConstraintChange>,
+}
+
+/// Constraint that forced a label change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Constraint {
+    /// Turn shorter than `min_turn_duration_ms`
+    MinTurnDuration,
+    /// Region exceeding `max_switches_per_second`
+    MaxSwitchRate,
+}
+
+impl Constraint {
+    /// Rule name recorded in token provenance
+    pub fn rule_name(&self) -> &'static str {
+        match self {
+            Constraint::MinTurnDuration => "min_turn_duration",
+            Constraint::MaxSwitchRate => "max_switch_rate",
+        }
+    }
+
+    /// Reason code recorded in token provenance
+    pub fn reason(&self) -> ReasonCode {
+        match self {
+            Constraint::MinTurnDuration => ReasonCode::MinTurnConstraint,
+            Constraint::MaxSwitchRate => ReasonCode::SwitchRateConstraint,
+        }
+    }
+}
+
+/// How a constraint violation was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintAction {
+    /// A Stage 2 relabel was undone
+    RevertRelabel,
+    /// A short turn was merged into the surrounding speaker
+    MergeTurn,
+}
+
+/// A single token label change forced by a constraint
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintChange {
+    /// Index of the token in the transcript
+    pub token_index: usize,
+    /// ID of the token
+    pub token_id: String,
+    /// Speaker before the constraint was applied
+    pub from_speaker: u32,
+    /// Speaker after the constraint was applied
+    pub to_speaker: u32,
+    /// Constraint that was violated
+    pub constraint: Constraint,
+    /// How the violation was resolved
+    pub action: ConstraintAction,
+}
+
+/// A relabel applied by the weighted vote, kept so constraints can revert it
+#[derive(Debug, Clone)]
+struct AppliedRelabel {
+    token_index: usize,
+    from_speaker: u32,
+    to_speaker: u32,
+    weight: f64,
 }
 
 /// Candidate label for a token from a window
@@ -43,19 +213,25 @@ pub struct Stage2Result {
 struct LabelCandidate {
     speaker: u32,
     window_id: String,
+    reason: ReasonCode,
     weight: f64,
 }
 
 /// Execute Stage 2: Global reconciliation
 ///
 /// Because windows overlap, we may get conflicting edits. This stage:
-/// 1. Collects all candidate labels for each token
-/// 2. Applies weighted voting to choose final labels
+/// 1. Collects all candidate labels for each token, including implicit
+///    "keep" votes from windows that left the token unchanged
+/// 2. Chooses final labels with the configured strategy: an independent
+///    weighted vote per token, or a global Viterbi decode that also uses
+///    speaker confidence and the tokens changed by heuristics
+///    (`heuristic_indices`)
 /// 3. Enforces constraints (min turn duration, max switches)
 pub fn execute_stage2(
     transcript: &mut TokenizedTranscript,
     windows: &WindowSet,
     patches: &[WindowPatch],
+    heuristic_indices: &[usize],
     config: &Stage2Config,
 ) -> Stage2Result {
     // Build a map of token_id -> list of candidate labels
@@ -74,14 +250,23 @@ pub fn execute_stage2(
         };
 
         for relabel in &patch.token_relabels {
-            // Find the token to get its timestamp for proximity calculation
-            let token_timestamp = transcript
-                .get_token(&relabel.token_id)
-                .map(|t| t.start_ms)
-                .unwrap_or(window.center_ms());
+            let token = transcript.get_token(&relabel.token_id);
+
+            // Labels confirmed in human review are never contested
+            if token.is_some_and(|t| t.locked) {
+                continue;
+            }
+
+            // Use the token's timestamp for proximity calculation
+            let token_timestamp = token.map(|t| t.start_ms).unwrap_or(window.center_ms());
 
             let proximity = window.proximity_to_center(token_timestamp);
-            let weight = proximity; // Could also include LLM confidence if available
+            let weight = config.vote_weighting.weight(
+                proximity,
+                relabel.confidence,
+                &patch.quality,
+                patch.is_uncertain(&relabel.token_id),
+            );
 
             candidates
                 .entry(relabel.token_id.clone())
@@ -89,83 +274,310 @@ pub fn execute_stage2(
                 .push(LabelCandidate {
                     speaker: relabel.new_speaker,
                     window_id: patch.window_id.clone(),
+                    reason: relabel.reason,
                     weight,
                 });
         }
     }
 
+    // Windows that saw a contested token and left it alone vote to keep it
+    if config.count_keep_votes {
+        add_keep_votes(transcript, windows, patches, config, &mut candidates);
+    }
+
     info!(
         "Stage 2: Reconciling {} token candidates",
         candidates.len()
     );
 
-    let mut tokens_relabeled = 0;
-    let mut conflicts_resolved = 0;
+    let mut applied = Vec::new();
+    let conflict_token_ids = match config.strategy {
+        ReconciliationStrategy::WeightedVote => {
+            resolve_by_vote(transcript, &candidates, config, &mut applied)
+        }
+        ReconciliationStrategy::Viterbi => resolve_by_viterbi(
+            transcript,
+            &candidates,
+            heuristic_indices,
+            config,
+            &mut applied,
+        ),
+    };
+    let tokens_relabeled = applied.len();
+    let conflicts_resolved = conflict_token_ids.len();
+
+    // Rebuild turns after all changes
+    let mut constraint_changes = Vec::new();
+    if tokens_relabeled > 0 {
+        rebuild_turns(transcript);
+
+        // Apply post-reconciliation constraints
+        constraint_changes = apply_constraints(transcript, config, &applied);
+    }
+
+    info!(
+        "Stage 2: {} tokens relabeled, {} conflicts resolved, {} constraint changes",
+        tokens_relabeled,
+        conflicts_resolved,
+        constraint_changes.len()
+    );
+
+    Stage2Result {
+        tokens_relabeled,
+        conflicts_resolved,
+        conflict_token_ids,
+        constraint_changes,
+    }
+}
+
+/// Resolve candidates with an independent weighted vote per token
+///
+/// Returns the IDs of the tokens whose conflicts were resolved.
+fn resolve_by_vote(
+    transcript: &mut TokenizedTranscript,
+    candidates: &HashMap<String, Vec<LabelCandidate>>,
+    config: &Stage2Config,
+    applied: &mut Vec<AppliedRelabel>,
+) -> Vec<String> {
+    let mut conflicts = Vec::new();
 
     // Apply weighted voting for each token
-    for (token_id, token_candidates) in &candidates {
-        let token = match transcript
+    for (token_id, token_candidates) in candidates {
+        let token_index = match transcript
             .tokens
-            .iter_mut()
-            .find(|t| t.token_id == *token_id)
+            .iter()
+            .position(|t| t.token_id == *token_id)
         {
-            Some(t) => t,
+            Some(i) => i,
             None => continue,
         };
+        let token = &mut transcript.tokens[token_index];
 
         // Check if this is a stable span that should be protected
-        if token.speaker_conf >= config.stable_span_confidence {
-            // Only override if multiple windows agree ON THE SAME alternative speaker
-            // Count how many windows propose each alternative speaker
-            let mut alternative_speaker_counts: HashMap<u32, usize> = HashMap::new();
-            for c in token_candidates.iter() {
-                if c.speaker != token.speaker {
-                    *alternative_speaker_counts.entry(c.speaker).or_default() += 1;
-                }
-            }
-
-            // Check if any single alternative speaker has enough consensus
-            let has_consensus = alternative_speaker_counts
-                .values()
-                .any(|&count| count >= config.min_windows_for_override);
-
-            if !has_consensus {
-                continue;
-            }
+        if is_protected_stable_span(token, token_candidates, config) {
+            continue;
         }
 
         // If there are multiple different candidates, we have a conflict
-        let unique_speakers: std::collections::HashSet<_> =
-            token_candidates.iter().map(|c| c.speaker).collect();
-        if unique_speakers.len() > 1 {
-            conflicts_resolved += 1;
+        if is_conflict(token_id, token_candidates) {
+            conflicts.push(token_id.clone());
         }
 
         // Weighted vote
         let final_speaker = weighted_vote(token_candidates);
 
         if final_speaker != token.speaker {
-            token.speaker = final_speaker;
-            tokens_relabeled += 1;
+            let weight = token_candidates
+                .iter()
+                .filter(|c| c.speaker == final_speaker)
+                .map(|c| c.weight)
+                .sum();
+            applied.push(AppliedRelabel {
+                token_index,
+                from_speaker: token.speaker,
+                to_speaker: final_speaker,
+                weight,
+            });
+            let (source, reason) = winning_source(token_candidates, final_speaker);
+            token.relabel(
+                final_speaker,
+                ChangeStage::Reconciliation,
+                source,
+                reason,
+                Some(weight),
+            );
         }
     }
 
-    // Rebuild turns after all changes
-    if tokens_relabeled > 0 {
-        rebuild_turns(transcript);
+    conflicts
+}
 
-        // Apply post-reconciliation constraints
-        apply_constraints(transcript, config);
+/// Resolve candidates by decoding the whole label sequence with Viterbi
+///
+/// Decoded flips of stable spans are dropped unless enough windows agree,
+/// as in the weighted vote. Flips no window voted for are recorded with
+/// source "viterbi" and reason `Smoothing`.
+///
+/// Returns the IDs of the tokens whose conflicts were resolved.
+fn resolve_by_viterbi(
+    transcript: &mut TokenizedTranscript,
+    candidates: &HashMap<String, Vec<LabelCandidate>>,
+    heuristic_indices: &[usize],
+    config: &Stage2Config,
+    applied: &mut Vec<AppliedRelabel>,
+) -> Vec<String> {
+    let mut conflicts = Vec::new();
+
+    // Accumulate vote weight per token index and speaker
+    let mut votes: HashMap<usize, HashMap<u32, f64>> = HashMap::new();
+    for (token_id, token_candidates) in candidates {
+        let Some(token_index) = transcript.tokens.iter().position(|t| t.token_id == *token_id)
+        else {
+            continue;
+        };
+        if is_conflict(token_id, token_candidates) {
+            conflicts.push(token_id.clone());
+        }
+        let speaker_votes = votes.entry(token_index).or_default();
+        for candidate in token_candidates {
+            *speaker_votes.entry(candidate.speaker).or_default() += candidate.weight;
+        }
     }
 
-    info!(
-        "Stage 2: {} tokens relabeled, {} conflicts resolved",
-        tokens_relabeled, conflicts_resolved
+    let decoded = viterbi_decode(transcript, &votes, heuristic_indices, &config.smoothing);
+
+    for (token_index, speaker) in decoded.into_iter().enumerate() {
+        let token = &mut transcript.tokens[token_index];
+        if token.speaker != speaker {
+            // Stable spans get the same protection as in the weighted vote;
+            // a stable token no window covered has no consensus and stays put
+            let token_candidates = candidates.get(&token.token_id).map_or(&[][..], |c| c);
+            if is_protected_stable_span(token, token_candidates, config) {
+                continue;
+            }
+            let weight = votes
+                .get(&token_index)
+                .and_then(|v| v.get(&speaker))
+                .copied()
+                .unwrap_or(0.0);
+            applied.push(AppliedRelabel {
+                token_index,
+                from_speaker: token.speaker,
+                to_speaker: speaker,
+                weight,
+            });
+            // Tokens flipped purely by smoothing have no voting window
+            let (source, reason) = match winning_source(token_candidates, speaker) {
+                (source, _) if source.is_empty() => ("viterbi".to_string(), ReasonCode::Smoothing),
+                winner => winner,
+            };
+            token.relabel(speaker, ChangeStage::Reconciliation, source, reason, Some(weight));
+        }
+    }
+
+    conflicts
+}
+
+/// Whether a token is a stable span that its candidates cannot override
+///
+/// Tokens with speaker confidence of at least `stable_span_confidence` only
+/// change when `min_windows_for_override` windows agree on the same
+/// alternative speaker.
+fn is_protected_stable_span(
+    token: &Token,
+    candidates: &[LabelCandidate],
+    config: &Stage2Config,
+) -> bool {
+    let stable = token
+        .speaker_confidence()
+        .is_some_and(|c| c >= config.stable_span_confidence);
+    if !stable {
+        return false;
+    }
+
+    // Count how many windows propose each alternative speaker
+    let mut alternative_speaker_counts: HashMap<u32, usize> = HashMap::new();
+    for c in candidates {
+        if c.speaker != token.speaker {
+            *alternative_speaker_counts.entry(c.speaker).or_default() += 1;
+        }
+    }
+
+    // Protected unless a single alternative speaker has enough consensus
+    !alternative_speaker_counts
+        .values()
+        .any(|&count| count >= config.min_windows_for_override)
+}
+
+/// Check whether candidates disagree, logging them if so
+fn is_conflict(token_id: &str, candidates: &[LabelCandidate]) -> bool {
+    let unique_speakers: HashSet<_> = candidates.iter().map(|c| c.speaker).collect();
+    if unique_speakers.len() <= 1 {
+        return false;
+    }
+
+    debug!(
+        "Token {}: conflicting candidates {:?}",
+        token_id,
+        candidates
+            .iter()
+            .map(|c| (c.window_id.as_str(), c.speaker, c.weight))
+            .collect::<Vec<_>>()
     );
+    true
+}
 
-    Stage2Result {
-        tokens_relabeled,
-        conflicts_resolved,
+/// Windows that voted for the winning speaker, and the strongest reason given
+///
+/// Returns the comma-separated window IDs and the reason code of the
+/// highest-weight candidate for `speaker`.
+fn winning_source(candidates: &[LabelCandidate], speaker: u32) -> (String, ReasonCode) {
+    let winners: Vec<&LabelCandidate> =
+        candidates.iter().filter(|c| c.speaker == speaker).collect();
+    let source = winners
+        .iter()
+        .map(|c| c.window_id.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let reason = winners
+        .iter()
+        .max_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|c| c.reason)
+        .unwrap_or(ReasonCode::JitterShortTurn);
+    (source, reason)
+}
+
+/// Add implicit "keep current speaker" votes from every processed window
+///
+/// Only tokens that at least one window proposed to relabel are considered;
+/// every other window covering such a token votes for its current speaker,
+/// weighted exactly like an explicit relabel without a reported confidence.
+fn add_keep_votes(
+    transcript: &TokenizedTranscript,
+    windows: &WindowSet,
+    patches: &[WindowPatch],
+    config: &Stage2Config,
+    candidates: &mut HashMap<String, Vec<LabelCandidate>>,
+) {
+    for patch in patches {
+        let window = match windows.windows.iter().find(|w| w.window_id == patch.window_id) {
+            Some(w) => w,
+            None => continue,
+        };
+
+        let relabeled: HashSet<&str> = patch
+            .token_relabels
+            .iter()
+            .map(|r| r.token_id.as_str())
+            .collect();
+
+        for &idx in &window.token_indices {
+            let token = match transcript.tokens.get(idx) {
+                Some(t) => t,
+                None => continue,
+            };
+            if relabeled.contains(token.token_id.as_str()) {
+                continue;
+            }
+            let Some(token_candidates) = candidates.get_mut(&token.token_id) else {
+                continue;
+            };
+
+            let proximity = window.proximity_to_center(token.start_ms);
+            let weight = config.vote_weighting.weight(
+                proximity,
+                None,
+                &patch.quality,
+                patch.is_uncertain(&token.token_id),
+            );
+
+            token_candidates.push(LabelCandidate {
+                speaker: token.speaker,
+                window_id: patch.window_id.clone(),
+                reason: ReasonCode::DoNotChange,
+                weight,
+            });
+        }
     }
 }
 
@@ -185,7 +597,15 @@ fn weighted_vote(candidates: &[LabelCandidate]) -> u32 {
 }
 
 /// Apply post-reconciliation constraints
-fn apply_constraints(transcript: &mut TokenizedTranscript, config: &Stage2Config) {
+///
+/// Returns every token label change the constraints forced.
+fn apply_constraints(
+    transcript: &mut TokenizedTranscript,
+    config: &Stage2Config,
+    applied: &[AppliedRelabel],
+) -> Vec<ConstraintChange> {
+    let mut changes = Vec::new();
+
     // Constraint 1: Minimum turn duration
     // Find turns that are too short and try to merge them
     let short_turns: Vec<usize> = transcript
@@ -197,49 +617,215 @@ fn apply_constraints(transcript: &mut TokenizedTranscript, config: &Stage2Config
         .collect();
 
     for &turn_idx in short_turns.iter().rev() {
-        // Try to merge with surrounding turns
-        let turn = &transcript.turns[turn_idx];
-
-        // Check if previous and next turns have the same speaker
-        let prev_speaker = if turn_idx > 0 {
-            Some(transcript.turns[turn_idx - 1].speaker)
-        } else {
-            None
-        };
-
-        let next_speaker = if turn_idx + 1 < transcript.turns.len() {
-            Some(transcript.turns[turn_idx + 1].speaker)
-        } else {
-            None
-        };
-
         // If surrounded by same speaker, relabel to that speaker
-        if let (Some(prev), Some(next)) = (prev_speaker, next_speaker) {
-            if prev == next && turn.speaker != prev {
-                for &token_idx in &turn.token_indices {
-                    if let Some(token) = transcript.tokens.get_mut(token_idx) {
-                        token.speaker = prev;
-                    }
-                }
-            }
+        if let Some(surrounding) = sandwiching_speaker(transcript, turn_idx) {
+            merge_turn(
+                transcript,
+                turn_idx,
+                surrounding,
+                Constraint::MinTurnDuration,
+                &mut changes,
+            );
         }
     }
 
     // Rebuild turns after constraint application
     rebuild_turns(transcript);
 
-    // Constraint 2: Maximum switches per second (soft - just log warnings)
-    let total_duration_s = transcript.duration_ms() as f64 / 1000.0;
-    if total_duration_s > 0.0 {
-        let switches_per_second = (transcript.turns.len() - 1) as f64 / total_duration_s;
-        if switches_per_second > config.max_switches_per_second {
-            tracing::warn!(
-                "High switch rate: {:.2} switches/sec (limit: {:.2})",
-                switches_per_second,
-                config.max_switches_per_second
+    // Constraint 2: Maximum switches per second within any local region
+    enforce_switch_rate(transcript, config, applied, &mut changes);
+
+    changes
+}
+
+/// Speaker on both sides of a turn, if the turn is sandwiched by one speaker
+fn sandwiching_speaker(transcript: &TokenizedTranscript, turn_idx: usize) -> Option<u32> {
+    if turn_idx == 0 || turn_idx + 1 >= transcript.turns.len() {
+        return None;
+    }
+
+    let prev = transcript.turns[turn_idx - 1].speaker;
+    let next = transcript.turns[turn_idx + 1].speaker;
+    (prev == next && transcript.turns[turn_idx].speaker != prev).then_some(prev)
+}
+
+/// Whether any token of a turn was locked by a reviewer
+fn turn_has_locked_token(transcript: &TokenizedTranscript, turn_idx: usize) -> bool {
+    transcript.turns[turn_idx]
+        .token_indices
+        .iter()
+        .any(|&i| transcript.tokens.get(i).is_some_and(|t| t.locked))
+}
+
+/// Relabel every token of a turn to the given speaker, recording the changes
+fn merge_turn(
+    transcript: &mut TokenizedTranscript,
+    turn_idx: usize,
+    speaker: u32,
+    constraint: Constraint,
+    changes: &mut Vec<ConstraintChange>,
+) {
+    let token_indices = transcript.turns[turn_idx].token_indices.clone();
+    for token_idx in token_indices {
+        if let Some(token) = transcript.tokens.get_mut(token_idx)
+            && token.speaker != speaker
+            && !token.locked
+        {
+            changes.push(ConstraintChange {
+                token_index: token_idx,
+                token_id: token.token_id.clone(),
+                from_speaker: token.speaker,
+                to_speaker: speaker,
+                constraint,
+                action: ConstraintAction::MergeTurn,
+            });
+            token.relabel(
+                speaker,
+                ChangeStage::Constraints,
+                constraint.rule_name(),
+                constraint.reason(),
+                None,
+            );
+        }
+    }
+}
+
+/// Enforce `max_switches_per_second` over sliding regions
+///
+/// Each region of `switch_rate_window_ms` starting at a speaker switch may
+/// contain at most `max_switches_per_second * region_seconds` switches. A
+/// violating region is resolved by reverting the lowest-weight Stage 2
+/// relabel inside it that reduces the switch count, or failing that by
+/// merging the shortest sandwiched turn with no locked tokens. Regions that cannot be resolved
+/// are left alone with a warning.
+fn enforce_switch_rate(
+    transcript: &mut TokenizedTranscript,
+    config: &Stage2Config,
+    applied: &[AppliedRelabel],
+    changes: &mut Vec<ConstraintChange>,
+) {
+    let region_ms = config.switch_rate_window_ms;
+    if region_ms == 0 || config.max_switches_per_second <= 0.0 {
+        return;
+    }
+    let max_switches =
+        (config.max_switches_per_second * region_ms as f64 / 1000.0).floor() as usize;
+    let mut reverted: HashSet<usize> = HashSet::new();
+    let mut resume_ms = 0u64;
+
+    while let Some((first_turn, last_turn)) =
+        find_switch_rate_violation(transcript, region_ms, max_switches, resume_ms)
+    {
+        let region_start = transcript.turns[first_turn].start_ms;
+        let region_end = transcript.turns[last_turn].end_ms;
+
+        // Prefer undoing the weakest relabel that actually reduces switching
+        let revert = applied
+            .iter()
+            .filter(|r| !reverted.contains(&r.token_index))
+            .filter(|r| {
+                transcript.tokens.get(r.token_index).is_some_and(|t| {
+                    t.speaker == r.to_speaker
+                        && t.start_ms >= region_start
+                        && t.start_ms <= region_end
+                })
+            })
+            .filter(|r| {
+                let current = transcript.tokens[r.token_index].speaker;
+                switches_around(transcript, r.token_index, r.from_speaker)
+                    < switches_around(transcript, r.token_index, current)
+            })
+            .min_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let Some(relabel) = revert {
+            let token = &mut transcript.tokens[relabel.token_index];
+            changes.push(ConstraintChange {
+                token_index: relabel.token_index,
+                token_id: token.token_id.clone(),
+                from_speaker: token.speaker,
+                to_speaker: relabel.from_speaker,
+                constraint: Constraint::MaxSwitchRate,
+                action: ConstraintAction::RevertRelabel,
+            });
+            token.relabel(
+                relabel.from_speaker,
+                ChangeStage::Constraints,
+                Constraint::MaxSwitchRate.rule_name(),
+                Constraint::MaxSwitchRate.reason(),
+                Some(relabel.weight),
             );
+            reverted.insert(relabel.token_index);
+            rebuild_turns(transcript);
+            continue;
+        }
+
+        // Otherwise merge the shortest turn sandwiched by a single speaker.
+        // Turns holding locked tokens cannot be fully merged, so skip them.
+        let shortest = (first_turn..=last_turn)
+            .filter(|&i| !turn_has_locked_token(transcript, i))
+            .filter_map(|i| sandwiching_speaker(transcript, i).map(|s| (i, s)))
+            .min_by_key(|&(i, _)| transcript.turns[i].duration_ms());
+
+        if let Some((turn_idx, speaker)) = shortest {
+            merge_turn(transcript, turn_idx, speaker, Constraint::MaxSwitchRate, changes);
+            rebuild_turns(transcript);
+            continue;
+        }
+
+        warn!(
+            "High switch rate between {}ms and {}ms could not be resolved (limit: {:.2}/sec)",
+            region_start, region_end, config.max_switches_per_second
+        );
+        resume_ms = transcript.turns[first_turn + 1].start_ms + 1;
+    }
+}
+
+/// Find the first region starting at or after `resume_ms` with too many switches
+///
+/// Returns the range of turn indices (inclusive) covering the region.
+fn find_switch_rate_violation(
+    transcript: &TokenizedTranscript,
+    region_ms: u64,
+    max_switches: usize,
+    resume_ms: u64,
+) -> Option<(usize, usize)> {
+    let turns = &transcript.turns;
+
+    // A switch happens at the start of every turn after the first
+    for first_switch in 1..turns.len() {
+        let region_start = turns[first_switch].start_ms;
+        if region_start < resume_ms {
+            continue;
+        }
+        let region_end = region_start + region_ms;
+
+        let last_switch = (first_switch..turns.len())
+            .take_while(|&i| turns[i].start_ms < region_end)
+            .last()
+            .unwrap_or(first_switch);
+
+        if last_switch - first_switch + 1 > max_switches {
+            return Some((first_switch - 1, last_switch));
         }
     }
+
+    None
+}
+
+/// Count speaker switches next to a token if it had the given speaker
+///
+/// Relabeling one token only changes the switches with its two neighbours,
+/// so comparing this count before and after is enough to tell whether a
+/// relabel reduces switching, without rescanning the whole transcript.
+fn switches_around(transcript: &TokenizedTranscript, token_index: usize, speaker: u32) -> usize {
+    let before = token_index.checked_sub(1).and_then(|i| transcript.tokens.get(i));
+    let after = transcript.tokens.get(token_index + 1);
+
+    [before, after]
+        .into_iter()
+        .flatten()
+        .filter(|t| t.speaker != speaker)
+        .count()
 }
 
 #[cfg(test)]
@@ -252,16 +838,19 @@ mod tests {
             LabelCandidate {
                 speaker: 0,
                 window_id: "w_0".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.8,
             },
             LabelCandidate {
                 speaker: 1,
                 window_id: "w_1".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.3,
             },
             LabelCandidate {
                 speaker: 0,
                 window_id: "w_2".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.5,
             },
         ];
@@ -275,12 +864,301 @@ mod tests {
         let candidates = vec![LabelCandidate {
             speaker: 2,
             window_id: "w_0".to_string(),
+            reason: ReasonCode::LexicalContinuity,
             weight: 1.0,
         }];
 
         assert_eq!(weighted_vote(&candidates), 2);
     }
 
+    fn keep_vote_fixture() -> (TokenizedTranscript, WindowSet, Vec<WindowPatch>) {
+        use crate::io::parse_deepgram_json;
+        use crate::models::{PatchNotes, ReasonCode, TokenRelabel, Window};
+
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.4, "confidence": 0.95, "speaker": 0},
+                            {"word": "yeah", "start": 0.5, "end": 0.9, "confidence": 0.95, "speaker": 0},
+                            {"word": "right", "start": 1.0, "end": 1.4, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let transcript = parse_deepgram_json(json).unwrap();
+        let target = transcript.tokens[2].token_id.clone();
+
+        let windows: Vec<Window> = (0..3)
+            .map(|i| Window {
+                window_id: format!("w_{}", i),
+                start_ms: 0,
+                end_ms: 1_400,
+                token_indices: vec![0, 1, 2],
+                anchor_prefix_indices: vec![],
+                anchor_suffix_indices: vec![],
+                is_problem_zone: true,
+                problem_types: vec![],
+                severity: 0.0,
+            })
+            .collect();
+
+        let patches = windows
+            .iter()
+            .map(|w| WindowPatch {
+                window_id: w.window_id.clone(),
+                token_relabels: if w.window_id == "w_0" {
+                    vec![TokenRelabel {
+                        token_id: target.clone(),
+                        new_speaker: 1,
+                        reason: ReasonCode::BackchannelAttribution,
+                        confidence: Some(0.9),
+                    }]
+                } else {
+                    vec![]
+                },
+                turn_edits: vec![],
+                violations: vec![],
+                notes: PatchNotes::default(),
+                quality: WindowQuality::default(),
+            })
+            .collect();
+
+        let window_set = WindowSet {
+            problem_window_indices: (0..windows.len()).collect(),
+            windows,
+        };
+
+        (transcript, window_set, patches)
+    }
+
+    #[test]
+    fn test_keep_votes_outweigh_single_relabel() {
+        let (mut transcript, windows, patches) = keep_vote_fixture();
+
+        let result = execute_stage2(&mut transcript, &windows, &patches, &[], &Stage2Config::default());
+
+        // Two windows kept the token, one relabeled it
+        assert_eq!(result.tokens_relabeled, 0);
+        assert_eq!(result.conflicts_resolved, 1);
+        assert_eq!(transcript.tokens[2].speaker, 0);
+    }
+
+    #[test]
+    fn test_relabel_wins_without_keep_votes() {
+        let (mut transcript, windows, patches) = keep_vote_fixture();
+        let config = Stage2Config {
+            count_keep_votes: false,
+            ..Default::default()
+        };
+
+        let result = execute_stage2(&mut transcript, &windows, &patches, &[], &config);
+
+        assert_eq!(result.tokens_relabeled, 1);
+        assert_eq!(transcript.tokens[2].speaker, 1);
+
+        let provenance = &transcript.tokens[2].provenance;
+        assert_eq!(provenance.len(), 1);
+        assert_eq!(provenance[0].stage, ChangeStage::Reconciliation);
+        assert_eq!(provenance[0].source, "w_0");
+        assert_eq!(provenance[0].to_speaker, 1);
+    }
+
+    #[test]
+    fn test_switch_rate_reverts_weakest_relabel() {
+        use crate::io::parse_deepgram_json;
+
+        // Five one-second tokens alternating 0/1 after two Stage 2 relabels
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "a", "start": 0.0, "end": 0.9, "confidence": 0.95, "speaker": 0},
+                            {"word": "b", "start": 1.0, "end": 1.9, "confidence": 0.95, "speaker": 1},
+                            {"word": "c", "start": 2.0, "end": 2.9, "confidence": 0.95, "speaker": 0},
+                            {"word": "d", "start": 3.0, "end": 3.9, "confidence": 0.95, "speaker": 1},
+                            {"word": "e", "start": 4.0, "end": 4.9, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let applied = vec![
+            AppliedRelabel {
+                token_index: 1,
+                from_speaker: 0,
+                to_speaker: 1,
+                weight: 0.2,
+            },
+            AppliedRelabel {
+                token_index: 3,
+                from_speaker: 0,
+                to_speaker: 1,
+                weight: 0.9,
+            },
+        ];
+        let config = Stage2Config {
+            min_turn_duration_ms: 0,
+            max_switches_per_second: 0.5,
+            switch_rate_window_ms: 4_000,
+            ..Default::default()
+        };
+
+        let changes = apply_constraints(&mut transcript, &config, &applied);
+
+        // Reverting the weaker relabel leaves 2 switches in the region
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].token_index, 1);
+        assert_eq!(changes[0].constraint, Constraint::MaxSwitchRate);
+        assert_eq!(changes[0].action, ConstraintAction::RevertRelabel);
+        assert_eq!(transcript.tokens[1].speaker, 0);
+        assert_eq!(transcript.tokens[3].speaker, 1);
+        let change = transcript.tokens[1].provenance.last().unwrap();
+        assert_eq!(change.reason, ReasonCode::SwitchRateConstraint);
+    }
+
+    #[test]
+    fn test_switch_rate_skips_locked_turns() {
+        use crate::io::parse_deepgram_json;
+
+        // Dense alternation where every speaker 1 token was locked in review
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "a", "start": 0.0, "end": 0.15, "confidence": 0.95, "speaker": 0},
+                            {"word": "b", "start": 0.2, "end": 0.35, "confidence": 0.95, "speaker": 1},
+                            {"word": "c", "start": 0.4, "end": 0.6, "confidence": 0.95, "speaker": 0},
+                            {"word": "d", "start": 0.6, "end": 0.75, "confidence": 0.95, "speaker": 1},
+                            {"word": "e", "start": 0.8, "end": 1.0, "confidence": 0.95, "speaker": 0}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        transcript.tokens[1].locked = true;
+        transcript.tokens[3].locked = true;
+        let config = Stage2Config {
+            min_turn_duration_ms: 0,
+            max_switches_per_second: 1.0,
+            switch_rate_window_ms: 2_000,
+            ..Default::default()
+        };
+
+        let changes = apply_constraints(&mut transcript, &config, &[]);
+
+        // Only the unlocked speaker 0 turn between them can be merged
+        assert!(changes.iter().all(|c| c.token_index == 2));
+        assert_eq!(transcript.tokens[1].speaker, 1);
+        assert_eq!(transcript.tokens[3].speaker, 1);
+    }
+
+    #[test]
+    fn test_viterbi_protects_stable_spans() {
+        use crate::io::parse_deepgram_json;
+
+        // "should" is a stable flip, "and" a low-confidence one; no window voted
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "should", "start": 0.3, "end": 0.6, "confidence": 0.95, "speaker": 1, "speaker_confidence": 0.85},
+                            {"word": "we", "start": 0.6, "end": 0.9, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "and", "start": 0.9, "end": 1.2, "confidence": 0.95, "speaker": 1, "speaker_confidence": 0.3},
+                            {"word": "go", "start": 1.2, "end": 1.5, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let mut applied = Vec::new();
+
+        resolve_by_viterbi(
+            &mut transcript,
+            &HashMap::new(),
+            &[],
+            &Stage2Config::default(),
+            &mut applied,
+        );
+
+        assert_eq!(transcript.tokens[1].speaker, 1);
+        assert_eq!(transcript.tokens[3].speaker, 0);
+        let change = transcript.tokens[3].provenance.last().unwrap();
+        assert_eq!(change.source, "viterbi");
+        assert_eq!(change.reason, ReasonCode::Smoothing);
+    }
+
+    #[test]
+    fn test_viterbi_runs_without_patches() {
+        use crate::io::parse_deepgram_json;
+
+        // No window returned a patch, as in heuristics-only runs
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "we", "start": 0.3, "end": 0.5, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "should", "start": 0.5, "end": 0.8, "confidence": 0.95, "speaker": 1, "speaker_confidence": 0.3},
+                            {"word": "ship", "start": 0.8, "end": 1.1, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "it", "start": 1.1, "end": 1.3, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let mut transcript = parse_deepgram_json(json).unwrap();
+        let windows = WindowSet {
+            windows: vec![],
+            problem_window_indices: vec![],
+        };
+        let config = Stage2Config {
+            strategy: ReconciliationStrategy::Viterbi,
+            ..Default::default()
+        };
+
+        let result = execute_stage2(&mut transcript, &windows, &[], &[], &config);
+
+        assert_eq!(result.tokens_relabeled, 1);
+        assert_eq!(transcript.tokens[2].speaker, 0);
+        assert_eq!(transcript.turns.len(), 1);
+    }
+
+    #[test]
+    fn test_vote_weighting() {
+        let weighting = VoteWeighting::default();
+        let quality = WindowQuality::default();
+
+        // Full proximity, full confidence, untouched budget, no cost change
+        let full = weighting.weight(1.0, Some(1.0), &quality, false);
+        assert!((full - 1.0).abs() < 0.001);
+
+        // Missing confidence falls back to the default
+        let defaulted = weighting.weight(1.0, None, &quality, false);
+        assert!((defaulted - weighting.default_confidence).abs() < 0.001);
+
+        // Uncertain tokens are penalised
+        let uncertain = weighting.weight(1.0, Some(1.0), &quality, true);
+        assert!((uncertain - 0.5).abs() < 0.001);
+
+        // Cost increases and spent budget reduce window quality
+        let worse = WindowQuality {
+            budget_margin: 0.0,
+            cost_delta: 10.0,
+        };
+        assert!((weighting.window_quality(&worse) - 0.25).abs() < 0.001);
+    }
+
     #[test]
     fn test_stable_span_requires_consensus() {
         // Test that stable spans are only overridden when multiple windows
@@ -298,16 +1176,19 @@ mod tests {
             LabelCandidate {
                 speaker: 1,
                 window_id: "w_0".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.9,
             },
             LabelCandidate {
                 speaker: 2,
                 window_id: "w_1".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.9,
             },
             LabelCandidate {
                 speaker: 3,
                 window_id: "w_2".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.9,
             },
         ];
@@ -331,16 +1212,19 @@ mod tests {
             LabelCandidate {
                 speaker: 1,
                 window_id: "w_0".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.9,
             },
             LabelCandidate {
                 speaker: 1,
                 window_id: "w_1".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.9,
             },
             LabelCandidate {
                 speaker: 2,
                 window_id: "w_2".to_string(),
+                reason: ReasonCode::LexicalContinuity,
                 weight: 0.9,
             },
         ];
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::TokenizedTranscript;
+
+/// Configuration for global HMM/Viterbi label smoothing
+#[derive(Debug, Clone)]
+pub struct SmoothingConfig {
+    /// Log-score cost of switching speaker between adjacent tokens
+    pub switch_penalty: f64,
+    /// Gap in milliseconds at which the full pause discount applies
+    pub pause_ms: u64,
+    /// Fraction of the switch penalty waived after a pause of `pause_ms`
+    pub pause_discount: f64,
+    /// Log-score bonus per unit of LLM vote weight for a speaker
+    pub llm_vote_weight: f64,
+    /// Log-score bonus for the current label of tokens set by heuristics
+    pub heuristic_weight: f64,
+    /// Floor for emission probabilities to keep log scores finite
+    pub min_emission: f64,
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        Self {
+            switch_penalty: 1.5,
+            pause_ms: 1_000,
+            pause_discount: 0.75,
+            llm_vote_weight: 2.0,
+            heuristic_weight: 1.0,
+            min_emission: 0.01,
+        }
+    }
+}
+
+/// Decode the most likely speaker sequence with Viterbi
+///
+/// Speaker labels are treated as a hidden sequence over the token stream:
+/// - Emissions start from the speaker confidence (the current label gets
+///   `0.5 + 0.5 * conf`, or 0.5 when unknown; the rest is shared by the
+///   other speakers), plus a bonus for labels set by heuristics and for
+///   every LLM vote.
+/// - Transitions charge `switch_penalty` for a speaker change, discounted
+///   by the pause between the two tokens so changes after silence are cheap.
+/// - Tokens locked by human review can only keep their current label.
+///
+/// `votes` maps token index to accumulated vote weight per speaker.
+/// Returns the decoded speaker for every token.
+pub fn viterbi_decode(
+    transcript: &TokenizedTranscript,
+    votes: &HashMap<usize, HashMap<u32, f64>>,
+    heuristic_indices: &[usize],
+    config: &SmoothingConfig,
+) -> Vec<u32> {
+    let tokens = &transcript.tokens;
+    if tokens.is_empty() {
+        return vec![];
+    }
+
+    // State space: every speaker in the transcript or proposed by a vote
+    let mut speakers = transcript.speakers.clone();
+    for speaker_votes in votes.values() {
+        for &speaker in speaker_votes.keys() {
+            if !speakers.contains(&speaker) {
+                speakers.push(speaker);
+            }
+        }
+    }
+    for token in tokens {
+        if !speakers.contains(&token.speaker) {
+            speakers.push(token.speaker);
+        }
+    }
+    speakers.sort();
+
+    let heuristic_indices: HashSet<usize> = heuristic_indices.iter().copied().collect();
+    let emission = |i: usize, state: usize| -> f64 {
+        let token = &tokens[i];
+        let speaker = speakers[state];
+        if token.locked && speaker != token.speaker {
+            return f64::NEG_INFINITY;
+        }
+        let current = 0.5 + 0.5 * token.speaker_confidence().unwrap_or(0.0).clamp(0.0, 1.0);
+        let probability = if speaker == token.speaker {
+            current
+        } else if speakers.len() > 1 {
+            (1.0 - current) / (speakers.len() - 1) as f64
+        } else {
+            0.0
+        };
+
+        let mut score = probability.max(config.min_emission).ln();
+        if speaker == token.speaker && heuristic_indices.contains(&i) {
+            score += config.heuristic_weight;
+        }
+        if let Some(weight) = votes.get(&i).and_then(|v| v.get(&speaker)) {
+            score += config.llm_vote_weight * weight;
+        }
+        score
+    };
+
+    let n_states = speakers.len();
+    let mut scores: Vec<f64> = (0..n_states).map(|s| emission(0, s)).collect();
+    let mut backpointers: Vec<Vec<usize>> = Vec::with_capacity(tokens.len());
+
+    for i in 1..tokens.len() {
+        let gap_ms = tokens[i].start_ms.saturating_sub(tokens[i - 1].end_ms);
+        let penalty = switch_penalty(gap_ms, config);
+
+        let mut next_scores = vec![f64::NEG_INFINITY; n_states];
+        let mut pointers = vec![0usize; n_states];
+
+        for (state, next_score) in next_scores.iter_mut().enumerate() {
+            for (prev, &prev_score) in scores.iter().enumerate() {
+                let transition = if prev == state { 0.0 } else { -penalty };
+                let candidate = prev_score + transition;
+                if candidate > *next_score {
+                    *next_score = candidate;
+                    pointers[state] = prev;
+                }
+            }
+            *next_score += emission(i, state);
+        }
+
+        scores = next_scores;
+        backpointers.push(pointers);
+    }
+
+    // Backtrack from the best final state
+    let mut state = scores
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(s, _)| s)
+        .unwrap_or(0);
+
+    let mut path = vec![0usize; tokens.len()];
+    path[tokens.len() - 1] = state;
+    for i in (1..tokens.len()).rev() {
+        state = backpointers[i - 1][state];
+        path[i - 1] = state;
+    }
+
+    path.into_iter().map(|s| speakers[s]).collect()
+}
+
+/// Switch penalty after a gap, discounted linearly up to `pause_ms`
+fn switch_penalty(gap_ms: u64, config: &SmoothingConfig) -> f64 {
+    let pause_fraction = if config.pause_ms > 0 {
+        (gap_ms as f64 / config.pause_ms as f64).min(1.0)
+    } else {
+        1.0
+    };
+    config.switch_penalty * (1.0 - config.pause_discount.clamp(0.0, 1.0) * pause_fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+
+    fn transcript() -> TokenizedTranscript {
+        let json = r#"{
+            "results": {
+                "channels": [{
+                    "alternatives": [{
+                        "words": [
+                            {"word": "so", "start": 0.0, "end": 0.3, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "we", "start": 0.3, "end": 0.5, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "should", "start": 0.5, "end": 0.8, "confidence": 0.95, "speaker": 1, "speaker_confidence": 0.3},
+                            {"word": "ship", "start": 0.8, "end": 1.1, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9},
+                            {"word": "it", "start": 1.1, "end": 1.3, "confidence": 0.95, "speaker": 0, "speaker_confidence": 0.9}
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        parse_deepgram_json(json).unwrap()
+    }
+
+    #[test]
+    fn test_viterbi_smooths_isolated_low_confidence_flip() {
+        let transcript = transcript();
+        let decoded = viterbi_decode(&transcript, &HashMap::new(), &[], &SmoothingConfig::default());
+
+        assert_eq!(decoded, vec![0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_viterbi_respects_strong_votes() {
+        let transcript = transcript();
+        let mut votes = HashMap::new();
+        votes.insert(2, HashMap::from([(1u32, 3.0)]));
+
+        let decoded = viterbi_decode(&transcript, &votes, &[], &SmoothingConfig::default());
+
+        assert_eq!(decoded, vec![0, 0, 1, 0, 0]);
+    }
+}
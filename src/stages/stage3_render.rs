@@ -4,9 +4,14 @@ use std::path::Path;
 use anyhow::Result;
 use tracing::info;
 
-use crate::io::{HumanTranscript, MachineTranscript, TranscriptMetadata};
+use crate::io::{
+    HtmlReport, HumanRenderOptions, HumanTranscript, MachineTranscript, TranscriptAnalytics,
+    TranscriptDiff, TranscriptMetadata,
+};
 use crate::models::{SpeakerIdentification, TokenizedTranscript};
 
+use super::ProblemZone;
+
 /// Configuration for Stage 3 rendering
 #[derive(Debug, Clone)]
 pub struct Stage3Config {
@@ -14,6 +19,15 @@ pub struct Stage3Config {
     pub generate_machine: bool,
     /// Whether to generate human-readable output
     pub generate_human: bool,
+    /// Whether to generate the original vs. corrected diff
+    pub generate_diff: bool,
+    /// Whether to generate the interactive HTML report
+    pub generate_report: bool,
+    /// Whether to generate speaker analytics
+    pub generate_analytics: bool,
+    /// Layout and labeling of the human-readable transcript; its timestamp
+    /// options also apply to the diff, report and analytics
+    pub human: HumanRenderOptions,
 }
 
 impl Default for Stage3Config {
@@ -21,6 +35,10 @@ impl Default for Stage3Config {
         Self {
             generate_machine: true,
             generate_human: true,
+            generate_diff: true,
+            generate_report: true,
+            generate_analytics: true,
+            human: HumanRenderOptions::default(),
         }
     }
 }
@@ -32,19 +50,45 @@ pub struct Stage3Result {
     pub machine_path: Option<std::path::PathBuf>,
     /// Path to human transcript (if generated)
     pub human_path: Option<std::path::PathBuf>,
+    /// Path to diff (if generated)
+    pub diff_path: Option<std::path::PathBuf>,
+    /// Path to HTML report (if generated)
+    pub report_path: Option<std::path::PathBuf>,
+    /// Path to speaker analytics (if generated)
+    pub analytics_path: Option<std::path::PathBuf>,
+}
+
+/// Output paths for Stage 3; outputs without a path are not written
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stage3Outputs<'a> {
+    /// Machine-readable transcript (JSON)
+    pub machine: Option<&'a Path>,
+    /// Human-readable transcript (text)
+    pub human: Option<&'a Path>,
+    /// Original vs. corrected diff (HTML if the path ends in `.html`, else text)
+    pub diff: Option<&'a Path>,
+    /// Interactive HTML report
+    pub report: Option<&'a Path>,
+    /// Speaker analytics (JSON if the path ends in `.json`, else text)
+    pub analytics: Option<&'a Path>,
 }
 
 /// Execute Stage 3: Rendering
 ///
-/// Produces two output views:
+/// Produces up to five output views:
 /// 1. Machine transcript: JSON with tokens, final speaker IDs, and timestamps
 /// 2. Human transcript: Formatted text with speaker labels and turns
+/// 3. Diff: Original vs. corrected turns side-by-side with change annotations
+/// 4. Report: Interactive HTML page with speaker colors, tooltips, shaded
+///    problem zones and a timeline
+/// 5. Analytics: Talk time, turn-taking and per-minute activity per speaker
+#[allow(clippy::too_many_arguments)]
 pub fn execute_stage3(
     transcript: &TokenizedTranscript,
     original_speakers: &[u32],
+    problem_zones: &[ProblemZone],
     metadata: TranscriptMetadata,
-    machine_output: Option<&Path>,
-    human_output: Option<&Path>,
+    outputs: &Stage3Outputs,
     config: &Stage3Config,
     speaker_names: Option<&HashMap<u32, String>>,
     speaker_identifications: Option<Vec<SpeakerIdentification>>,
@@ -52,36 +96,78 @@ pub fn execute_stage3(
     let mut result = Stage3Result {
         machine_path: None,
         human_path: None,
+        diff_path: None,
+        report_path: None,
+        analytics_path: None,
     };
 
     // Generate machine transcript
-    if config.generate_machine {
-        if let Some(path) = machine_output {
-            info!("Writing machine transcript to {:?}", path);
-            let machine = MachineTranscript::from_transcript(
-                transcript,
-                original_speakers,
-                metadata,
-                speaker_names,
-                speaker_identifications,
-            );
-            machine.write_json(path)?;
-            result.machine_path = Some(path.to_path_buf());
-        }
+    if config.generate_machine
+        && let Some(path) = outputs.machine
+    {
+        info!("Writing machine transcript to {:?}", path);
+        let machine = MachineTranscript::from_transcript(
+            transcript,
+            original_speakers,
+            metadata,
+            speaker_names,
+            speaker_identifications,
+        );
+        machine.write_json(path)?;
+        result.machine_path = Some(path.to_path_buf());
     }
 
     // Generate human transcript
-    if config.generate_human {
-        if let Some(path) = human_output {
-            info!("Writing human transcript to {:?}", path);
-            let human = if let Some(names) = speaker_names {
-                HumanTranscript::with_speaker_names(transcript, names)
-            } else {
-                HumanTranscript::new(transcript)
-            };
-            human.write_file(path)?;
-            result.human_path = Some(path.to_path_buf());
+    if config.generate_human
+        && let Some(path) = outputs.human
+    {
+        info!("Writing human transcript to {:?}", path);
+        let human = if let Some(names) = speaker_names {
+            HumanTranscript::with_speaker_names(transcript, names)
+        } else {
+            HumanTranscript::new(transcript)
+        };
+        human.with_options(config.human.clone()).write_file(path)?;
+        result.human_path = Some(path.to_path_buf());
+    }
+
+    // Generate diff
+    if config.generate_diff
+        && let Some(path) = outputs.diff
+    {
+        info!("Writing diff to {:?}", path);
+        let mut diff = TranscriptDiff::new(transcript, original_speakers)
+            .with_options(config.human.clone());
+        if let Some(names) = speaker_names {
+            diff = diff.with_speaker_names(names);
         }
+        diff.write_file(path)?;
+        result.diff_path = Some(path.to_path_buf());
+    }
+
+    // Generate HTML report
+    if config.generate_report
+        && let Some(path) = outputs.report
+    {
+        info!("Writing HTML report to {:?}", path);
+        let mut report = HtmlReport::new(transcript, original_speakers, problem_zones)
+            .with_options(config.human.clone());
+        if let Some(names) = speaker_names {
+            report = report.with_speaker_names(names);
+        }
+        report.write_file(path)?;
+        result.report_path = Some(path.to_path_buf());
+    }
+
+    // Generate speaker analytics
+    if config.generate_analytics
+        && let Some(path) = outputs.analytics
+    {
+        info!("Writing speaker analytics to {:?}", path);
+        TranscriptAnalytics::from_transcript(transcript, speaker_names)
+            .with_options(config.human.clone())
+            .write_file(path)?;
+        result.analytics_path = Some(path.to_path_buf());
     }
 
     Ok(result)
@@ -96,5 +182,8 @@ mod tests {
         let config = Stage3Config::default();
         assert!(config.generate_machine);
         assert!(config.generate_human);
+        assert!(config.generate_diff);
+        assert!(config.generate_report);
+        assert!(config.generate_analytics);
     }
 }
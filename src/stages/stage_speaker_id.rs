@@ -40,7 +40,7 @@ pub async fn execute_speaker_id(
 /// Extracts turns from the transcript to provide context for identification.
 /// Prioritizes early turns (where introductions are likely) and turns with
 /// more content.
-fn build_speaker_context(
+pub(crate) fn build_speaker_context(
     transcript: &TokenizedTranscript,
     config: &SpeakerIdConfig,
 ) -> Vec<(u32, Vec<String>)> {
@@ -74,7 +74,7 @@ fn build_speaker_context(
                 .skip(2) // Skip already-selected first 2
                 .map(|(i, t)| (i, t.token_indices.len()))
                 .collect();
-            by_length.sort_by(|a, b| b.1.cmp(&a.1)); // Descending by length
+            by_length.sort_by_key(|&(_, len)| std::cmp::Reverse(len)); // Descending by length
 
             for (idx, _) in by_length
                 .into_iter()
@@ -129,7 +129,7 @@ pub fn parse_participants_string(input: &str) -> Vec<Participant> {
         .split(',')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
-        .map(|name| Participant::new(name))
+        .map(Participant::new)
         .collect()
 }
 
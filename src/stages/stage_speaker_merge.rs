@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+
+use crate::heuristics::micro_turns::rebuild_turns;
+use crate::heuristics::{cosine_similarity, word_counts};
+use crate::llm::speaker_merge_prompt::{
+    build_speaker_merge_system_prompt, build_speaker_merge_user_prompt,
+};
+use crate::llm::{AnthropicClient, Usage};
+use crate::models::{
+    ChangeStage, ReasonCode, SpeakerActivity, SpeakerIdConfig, SpeakerMerge, SpeakerMergeConfig,
+    SpeakerMergeResult, SpeakerMergeVerdict, TokenizedTranscript,
+};
+
+use super::stage_speaker_id::build_speaker_context;
+
+/// Execute the global speaker merge stage
+///
+/// Proposes merges of speaker IDs that look like one person split by the
+/// diarizer, optionally checks them with the LLM, then relabels every
+/// token of each merged speaker. Without a client only the heuristic
+/// confidence decides.
+pub async fn execute_speaker_merge(
+    client: Option<&AnthropicClient>,
+    transcript: &mut TokenizedTranscript,
+    config: &SpeakerMergeConfig,
+) -> Result<SpeakerMergeResult> {
+    let mut merges = propose_speaker_merges(transcript, config);
+    let mut usage = Usage::default();
+
+    if let Some(client) = client
+        && !merges.is_empty()
+    {
+        let candidates: Vec<u32> = merges
+            .iter()
+            .flat_map(|m| [m.from_speaker, m.to_speaker])
+            .collect();
+        let id_config = SpeakerIdConfig {
+            max_excerpts_per_speaker: config.max_excerpts_per_speaker,
+            ..Default::default()
+        };
+        let speaker_excerpts: Vec<(u32, Vec<String>)> =
+            build_speaker_context(transcript, &id_config)
+                .into_iter()
+                .filter(|(speaker, _)| candidates.contains(speaker))
+                .collect();
+
+        let system_prompt = build_speaker_merge_system_prompt();
+        let user_prompt = build_speaker_merge_user_prompt(&speaker_excerpts, &merges);
+        let (verdicts, check_usage) = client
+            .send_speaker_merge_request(&system_prompt, &user_prompt)
+            .await?;
+        apply_verdicts(&mut merges, &verdicts);
+        usage = check_usage;
+    }
+
+    let remapping = apply_speaker_merges(transcript, &mut merges, config.min_confidence);
+    Ok(SpeakerMergeResult {
+        tokens_relabeled: merges.iter().map(|m| m.tokens_relabeled).sum(),
+        merges,
+        remapping,
+        usage,
+    })
+}
+
+/// Propose merges for speaker pairs that look like one person
+///
+/// Each pair is scored from its turn structure (speaker changes between the
+/// two in mid-sentence, or activity in separate parts of the recording),
+/// the cosine similarity of their word counts and their words per turn.
+/// Pairs whose words overlap in time are never proposed. The speaker with
+/// less talk time is merged into the other.
+pub fn propose_speaker_merges(
+    transcript: &TokenizedTranscript,
+    config: &SpeakerMergeConfig,
+) -> Vec<SpeakerMerge> {
+    let activity = SpeakerActivity::from_transcript(transcript);
+    let mut merges = Vec::new();
+    for (i, a) in activity.iter().enumerate() {
+        for b in &activity[i + 1..] {
+            let (from, to) = if a.talk_ms < b.talk_ms { (a, b) } else { (b, a) };
+            if let Some(merge) = score_pair(transcript, from, to, config)
+                && merge.confidence >= config.min_candidate_confidence
+            {
+                merges.push(merge);
+            }
+        }
+    }
+    merges.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    merges
+}
+
+/// Fold LLM verdicts into the matching proposals' confidence and evidence
+///
+/// A same-person verdict averages both confidences; a different-people
+/// verdict scales the heuristic confidence down by the LLM's confidence.
+fn apply_verdicts(merges: &mut [SpeakerMerge], verdicts: &[SpeakerMergeVerdict]) {
+    for merge in merges.iter_mut() {
+        let Some(verdict) = verdicts.iter().find(|v| {
+            (v.from_speaker, v.to_speaker) == (merge.from_speaker, merge.to_speaker)
+                || (v.from_speaker, v.to_speaker) == (merge.to_speaker, merge.from_speaker)
+        }) else {
+            continue;
+        };
+        let llm_confidence = verdict.confidence.clamp(0.0, 1.0);
+        merge.confidence = if verdict.same_person {
+            (merge.confidence + llm_confidence) / 2.0
+        } else {
+            merge.confidence * (1.0 - llm_confidence)
+        };
+        merge.llm_same_person = Some(verdict.same_person);
+        merge
+            .evidence
+            .extend(verdict.evidence.iter().map(|e| format!("LLM: {}", e)));
+    }
+    merges.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+}
+
+/// Apply merges at or above `min_confidence`, most confident first
+///
+/// Chains resolve to their final speaker (merging 2 into 1 and then 1 into
+/// 0 relabels speaker 2 as 0). Locked tokens keep their label. Returns the
+/// final speaker for every merged speaker ID.
+pub fn apply_speaker_merges(
+    transcript: &mut TokenizedTranscript,
+    merges: &mut [SpeakerMerge],
+    min_confidence: f64,
+) -> BTreeMap<u32, u32> {
+    let mut remapping: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut merge_of: HashMap<u32, usize> = HashMap::new();
+    for (i, merge) in merges.iter_mut().enumerate() {
+        if merge.confidence < min_confidence || remapping.contains_key(&merge.from_speaker) {
+            continue;
+        }
+        let to = remapping
+            .get(&merge.to_speaker)
+            .copied()
+            .unwrap_or(merge.to_speaker);
+        if to == merge.from_speaker {
+            continue;
+        }
+        for target in remapping.values_mut() {
+            if *target == merge.from_speaker {
+                *target = to;
+            }
+        }
+        remapping.insert(merge.from_speaker, to);
+        merge_of.insert(merge.from_speaker, i);
+        merge.applied = true;
+    }
+
+    for token in transcript.tokens.iter_mut().filter(|t| !t.locked) {
+        let (Some(&target), Some(&i)) =
+            (remapping.get(&token.speaker), merge_of.get(&token.speaker))
+        else {
+            continue;
+        };
+        token.relabel(
+            target,
+            ChangeStage::SpeakerMerge,
+            "merge_speakers",
+            ReasonCode::SpeakerMerge,
+            Some(merges[i].confidence),
+        );
+        merges[i].tokens_relabeled += 1;
+    }
+
+    if merges.iter().any(|m| m.tokens_relabeled > 0) {
+        rebuild_turns(transcript);
+        let tokens = &transcript.tokens;
+        transcript
+            .speakers
+            .retain(|&s| tokens.iter().any(|t| t.speaker == s));
+    }
+
+    remapping
+}
+
+/// Score merging `from` into `to`; `None` if they ever talk at the same time
+fn score_pair(
+    transcript: &TokenizedTranscript,
+    from: &SpeakerActivity,
+    to: &SpeakerActivity,
+    config: &SpeakerMergeConfig,
+) -> Option<SpeakerMerge> {
+    if simultaneous_tokens(transcript, from.speaker, to.speaker, config.min_overlap_ms) > 0 {
+        return None;
+    }
+    let mut evidence = vec!["never speak at the same time".to_string()];
+
+    let lexical = cosine_similarity(
+        &word_counts(transcript, from.speaker),
+        &word_counts(transcript, to.speaker),
+    );
+    evidence.push(format!("word profiles {:.2} similar", lexical));
+
+    // Speaker changes between the two without a sentence end or pause
+    let mut changes = 0;
+    let mut continuations = 0;
+    for pair in transcript.turns.windows(2) {
+        let speakers = (pair[0].speaker, pair[1].speaker);
+        if speakers != (from.speaker, to.speaker) && speakers != (to.speaker, from.speaker) {
+            continue;
+        }
+        changes += 1;
+        // Without punctuation a sentence end can't be told apart
+        let mid_sentence = pair[0]
+            .token_indices
+            .last()
+            .and_then(|&i| transcript.tokens.get(i))
+            .and_then(|t| t.punctuated_word.as_deref())
+            .is_some_and(|w| !w.ends_with(['.', '?', '!']));
+        if mid_sentence && pair[1].pause_before_ms < config.continuation_gap_ms {
+            continuations += 1;
+        }
+    }
+    let continuation = continuations as f64 / changes.max(1) as f64;
+    if changes > 0 {
+        evidence.push(format!(
+            "{} of {} speaker changes between them are mid-sentence",
+            continuations, changes
+        ));
+    } else {
+        evidence.push("never take turns directly".to_string());
+    }
+
+    // Overlap of the spans from each speaker's first to last turn
+    let span = |speaker: u32| {
+        let mut turns = transcript.turns.iter().filter(|t| t.speaker == speaker);
+        let first = turns.next();
+        let last = turns.next_back().or(first);
+        let start = first.map_or(0, |t| t.start_ms);
+        (start, last.map_or(0, |t| t.end_ms).max(start))
+    };
+    let (from_start, from_end) = span(from.speaker);
+    let (to_start, to_end) = span(to.speaker);
+    let shared = from_end.min(to_end).saturating_sub(from_start.max(to_start));
+    let shorter = (from_end - from_start).min(to_end - to_start).max(1);
+    let separation = 1.0 - (shared as f64 / shorter as f64).min(1.0);
+    evidence.push(format!("active spans overlap {:.0}%", (1.0 - separation) * 100.0));
+
+    let words_per_turn = |a: &SpeakerActivity| a.words as f64 / a.turns.max(1) as f64;
+    let (from_wpt, to_wpt) = (words_per_turn(from), words_per_turn(to));
+    let turn_similarity = from_wpt.min(to_wpt) / from_wpt.max(to_wpt).max(f64::EPSILON);
+    evidence.push(format!("{:.1} and {:.1} words per turn", from_wpt, to_wpt));
+
+    // Separate spans alone also fit two people speaking at different
+    // times, so they count for less than mid-sentence changes
+    let structure = continuation.max(0.7 * separation);
+    let confidence = 0.6 * structure + 0.25 * lexical + 0.15 * turn_similarity;
+
+    Some(SpeakerMerge {
+        from_speaker: from.speaker,
+        to_speaker: to.speaker,
+        confidence,
+        evidence,
+        llm_same_person: None,
+        applied: false,
+        tokens_relabeled: 0,
+    })
+}
+
+/// Tokens of speaker `a` overlapping a token of speaker `b` by at least
+/// `min_overlap_ms`
+fn simultaneous_tokens(
+    transcript: &TokenizedTranscript,
+    a: u32,
+    b: u32,
+    min_overlap_ms: u64,
+) -> usize {
+    let times = |speaker: u32| {
+        let mut times: Vec<(u64, u64)> = transcript
+            .tokens
+            .iter()
+            .filter(|t| t.speaker == speaker)
+            .map(|t| (t.start_ms, t.end_ms))
+            .collect();
+        times.sort_unstable();
+        times
+    };
+    let (a_times, b_times) = (times(a), times(b));
+
+    let mut first = 0;
+    a_times
+        .iter()
+        .filter(|&&(start, end)| {
+            while b_times.get(first).is_some_and(|&(_, b_end)| b_end <= start) {
+                first += 1;
+            }
+            b_times[first.min(b_times.len())..]
+                .iter()
+                .take_while(|&&(b_start, _)| b_start < end)
+                .any(|&(b_start, b_end)| {
+                    end.min(b_end).saturating_sub(start.max(b_start)) >= min_overlap_ms
+                })
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::parse_deepgram_json;
+
+    #[test]
+    fn test_propose_and_apply_speaker_merges() {
+        // Speaker 1 always finishes speaker 0's sentence, then speaker 2
+        // answers with a sentence of their own
+        let words: Vec<String> = (0..45)
+            .map(|i| {
+                let (speaker, word) = match i % 9 {
+                    0..=2 => (0, "so"),
+                    3..=4 => (1, "we"),
+                    5 => (1, "agree."),
+                    6..=7 => (2, "yes"),
+                    _ => (2, "right."),
+                };
+                format!(
+                    r#"{{"word": "{}", "punctuated_word": "{}", "start": {}.0, "end": {}.8, "confidence": 0.9, "speaker": {}}}"#,
+                    word.trim_end_matches('.'),
+                    word,
+                    i,
+                    i,
+                    speaker
+                )
+            })
+            .collect();
+        let json = format!(
+            r#"{{"results": {{"channels": [{{"alternatives": [{{"words": [{}]}}]}}]}}}}"#,
+            words.join(",")
+        );
+        let mut transcript = parse_deepgram_json(&json).unwrap();
+        let config = SpeakerMergeConfig::default();
+
+        let mut merges = propose_speaker_merges(&transcript, &config);
+        assert_eq!(merges.len(), 1);
+        assert_eq!((merges[0].from_speaker, merges[0].to_speaker), (1, 0));
+        assert!(merges[0].confidence >= config.min_confidence);
+
+        let remapping = apply_speaker_merges(&mut transcript, &mut merges, config.min_confidence);
+        assert_eq!(remapping.get(&1), Some(&0));
+        assert!(merges[0].applied);
+        assert_eq!(merges[0].tokens_relabeled, 15);
+        assert_eq!(transcript.speakers, vec![0, 2]);
+        assert_eq!(transcript.tokens[3].provenance[0].stage, ChangeStage::SpeakerMerge);
+    }
+}